@@ -25,10 +25,26 @@
 //!     Ok(())
 //! }
 //! ```
-use crate::HandleToken;
+//!
+//! Request access to the camera and get the PipeWire remote in one go
+//!
+//! ```no_run
+//! use ashpd::desktop::camera;
+//! use std::os::unix::io::AsRawFd;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     let remote = camera::request_access()?;
+//!     println!("PipeWire remote fd: {}", remote.as_raw_fd());
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::{BasicResponse, Error, HandleToken, RequestProxy};
 use std::collections::HashMap;
-use zbus::{dbus_proxy, fdo::Result};
-use zvariant::{Fd, OwnedObjectPath, Value};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::sync::{Arc, Mutex};
+use zbus::{dbus_proxy, fdo::PropertiesProxy, fdo::Result};
+use zvariant::{Fd, OwnedFd, OwnedObjectPath, Value};
 use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
 
 #[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
@@ -83,3 +99,63 @@ trait Camera {
     #[dbus_proxy(property, name = "version")]
     fn version(&self) -> Result<u32>;
 }
+
+impl<'a> CameraProxy<'a> {
+    /// Waits for a camera to be plugged in or unplugged,
+    /// then calls `callback` with whether there's any camera available.
+    ///
+    /// It blocks until the [`is_camera_present`] property changes once,
+    /// call it again to keep watching.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Called on the next change of the [`is_camera_present`] property.
+    ///
+    /// [`is_camera_present`]: ./struct.CameraProxy.html#method.is_camera_present
+    pub fn on_camera_present_changed<F>(&self, callback: F) -> Result<()>
+    where
+        F: FnOnce(bool),
+    {
+        let properties =
+            PropertiesProxy::new_for(self.connection(), self.destination(), self.path())?;
+        let changed_value = Arc::new(Mutex::new(None));
+        let value = changed_value.clone();
+        properties.connect_properties_changed(move |interface, changed, _| {
+            if interface == "org.freedesktop.portal.Camera" {
+                if let Some(Value::Bool(is_camera_present)) = changed.get("IsCameraPresent") {
+                    *value.lock().unwrap() = Some(*is_camera_present);
+                }
+            }
+            Ok(())
+        })?;
+        loop {
+            if let Some(is_camera_present) = changed_value.lock().unwrap().take() {
+                callback(is_camera_present);
+                return Ok(());
+            }
+            properties.next_signal()?;
+        }
+    }
+}
+
+/// Requests access to the camera and opens the PipeWire remote where the camera nodes are available.
+///
+/// Returns an owned file descriptor of the PipeWire remote.
+pub fn request_access() -> crate::Result<OwnedFd> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = CameraProxy::new(&connection)?;
+    if !proxy.is_camera_present()? {
+        return Err(Error::NotAvailable("there's no camera".to_string()));
+    }
+
+    let handle = proxy.access_camera(CameraAccessOptions::default())?;
+    let request = RequestProxy::new(&connection, &handle)?;
+    request.receive_response::<BasicResponse>()??;
+
+    // The generated method drops the reply, which closes the file descriptors it carries,
+    // so take them over from the reply itself.
+    let mut reply = proxy.call_method("OpenPipeWireRemote", &(HashMap::<&str, Value>::new(),))?;
+    let fd = reply.body::<Fd>().map_err(zbus::Error::from)?.as_raw_fd();
+    reply.disown_fds();
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
@@ -5,16 +5,16 @@
 //! ```no_run
 //! use ashpd::desktop::email::{EmailProxy, EmailOptions};
 //! use ashpd::{RequestProxy, Response, WindowIdentifier, BasicResponse as Basic};
-//! use zbus::{fdo::Result, Connection};
+//! use zbus::Connection;
 //! use zvariant::Fd;
 //! use std::fs::File;
 //! use std::os::unix::io::AsRawFd;
 //!
-//! fn main() -> Result<()> {
+//! fn main() -> ashpd::Result<()> {
 //!     let connection = Connection::new_session()?;
 //!     let proxy = EmailProxy::new(&connection)?;
 //!
-//!     let file = File::open("/home/bilelmoussaoui/Downloads/adwaita-night.jpg").unwrap();
+//!     let file = File::open("/home/bilelmoussaoui/Downloads/adwaita-night.jpg")?;
 //!
 //!     let handle = proxy.compose_email(
 //!         WindowIdentifier::default(),
@@ -33,12 +33,77 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Forward a `mailto:` link with an attachment
+//!
+//! ```no_run
+//! use ashpd::desktop::email::{EmailOptions, EmailProxy};
+//! use ashpd::{RequestProxy, Response, WindowIdentifier, BasicResponse as Basic};
+//! use std::path::Path;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     let connection = zbus::Connection::new_session()?;
+//!     let proxy = EmailProxy::new(&connection)?;
+//!
+//!     let options = EmailOptions::from_mailto("mailto:test@gmail.com?subject=Hello%20there")
+//!         .expect("invalid mailto link")
+//!         .attach_path(Path::new("/home/bilelmoussaoui/Downloads/adwaita-night.jpg"))?;
+//!
+//!     let handle = proxy.compose_email(WindowIdentifier::default(), options)?;
+//!     let request = RequestProxy::new(&connection, &handle)?;
+//!     request.on_response(|r: Response<Basic>| {
+//!         println!("{}", r.is_ok());
+//!     })?;
+//!
+//!     Ok(())
+//! }
+//! ```
 use crate::{HandleToken, WindowIdentifier};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
 use zbus::{dbus_proxy, fdo::Result};
-use zvariant::{Fd, OwnedObjectPath};
-use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
+use zvariant::{Fd, OwnedObjectPath, SerializeValue, Signature};
+use zvariant_derive::{DeserializeDict, TypeDict};
+
+/// The characters to escape in the addresses & header values of a `mailto:` URI.
+const MAILTO_ESCAPE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'@')
+    .remove(b'+')
+    .remove(b'!')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An error returned when validating the addresses of an email or parsing a `mailto:` URI.
+pub enum EmailError {
+    /// The address is not a valid RFC 5322 address.
+    InvalidAddress(String),
+    /// The URI is not a valid `mailto:` URI.
+    InvalidMailto(String),
+}
+
+impl fmt::Display for EmailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAddress(address) => write!(f, "Invalid email address: {}", address),
+            Self::InvalidMailto(uri) => write!(f, "Invalid mailto URI: {}", uri),
+        }
+    }
+}
+
+impl std::error::Error for EmailError {}
 
-#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+#[derive(Debug, Default)]
 /// Specified options for a compose email request.
 pub struct EmailOptions {
     /// A string that will be used as the last element of the handle.
@@ -57,6 +122,81 @@ pub struct EmailOptions {
     pub body: Option<String>,
     /// A list of file descriptors of files to attach.
     pub attachment_fds: Option<Vec<Fd>>,
+    // The files opened by `attach_path`, kept open until the options are dropped.
+    files: Vec<File>,
+}
+
+#[derive(DeserializeDict, TypeDict)]
+/// The fields of [`EmailOptions`] as they are sent on the bus.
+struct EmailOptionsDict {
+    handle_token: Option<HandleToken>,
+    address: Option<String>,
+    addresses: Option<Vec<String>>,
+    cc: Option<Vec<String>>,
+    bcc: Option<Vec<String>>,
+    subject: Option<String>,
+    body: Option<String>,
+    attachment_fds: Option<Vec<Fd>>,
+}
+
+impl zvariant::Type for EmailOptions {
+    fn signature() -> Signature<'static> {
+        EmailOptionsDict::signature()
+    }
+}
+
+impl Serialize for EmailOptions {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(ref handle_token) = self.handle_token {
+            map.serialize_entry("handle_token", &SerializeValue(handle_token))?;
+        }
+        if let Some(ref address) = self.address {
+            map.serialize_entry("address", &SerializeValue(address))?;
+        }
+        if let Some(ref addresses) = self.addresses {
+            map.serialize_entry("addresses", &SerializeValue(addresses))?;
+        }
+        if let Some(ref cc) = self.cc {
+            map.serialize_entry("cc", &SerializeValue(cc))?;
+        }
+        if let Some(ref bcc) = self.bcc {
+            map.serialize_entry("bcc", &SerializeValue(bcc))?;
+        }
+        if let Some(ref subject) = self.subject {
+            map.serialize_entry("subject", &SerializeValue(subject))?;
+        }
+        if let Some(ref body) = self.body {
+            map.serialize_entry("body", &SerializeValue(body))?;
+        }
+        if let Some(ref attachment_fds) = self.attachment_fds {
+            map.serialize_entry("attachment_fds", &SerializeValue(attachment_fds))?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for EmailOptions {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let dict = EmailOptionsDict::deserialize(deserializer)?;
+        Ok(Self {
+            handle_token: dict.handle_token,
+            address: dict.address,
+            addresses: dict.addresses,
+            cc: dict.cc,
+            bcc: dict.bcc,
+            subject: dict.subject,
+            body: dict.body,
+            attachment_fds: dict.attachment_fds,
+            files: Vec::new(),
+        })
+    }
 }
 
 impl EmailOptions {
@@ -112,6 +252,218 @@ impl EmailOptions {
         };
         self
     }
+
+    /// Opens the file at `path` and attaches it to the email.
+    ///
+    /// The file is kept open as long as the options.
+    pub fn attach_path(mut self, path: &Path) -> crate::Result<Self> {
+        let file = File::open(path)?;
+        self = self.attach(Fd::from(file.as_raw_fd()));
+        self.files.push(file);
+        Ok(self)
+    }
+
+    /// Checks that the address, addresses, CC and BCC are valid RFC 5322 email addresses.
+    ///
+    /// Only ASCII addresses are accepted, the internationalized ones of RFC 6532 are rejected.
+    /// This is done by [`EmailProxy::compose_email`] before calling the portal.
+    ///
+    /// [`EmailProxy::compose_email`]: ./struct.EmailProxy.html#method.compose_email
+    ///
+    /// ```
+    /// use ashpd::desktop::email::EmailOptions;
+    ///
+    /// assert!(EmailOptions::default().address("test@gmail.com").validate().is_ok());
+    /// assert!(EmailOptions::default().address("\"john doe\"@example.org").validate().is_ok());
+    ///
+    /// assert!(EmailOptions::default().address("test.gmail.com").validate().is_err());
+    /// assert!(EmailOptions::default().cc(vec!["test..a@gmail.com".to_string()]).validate().is_err());
+    /// assert!(EmailOptions::default().address("tést@gmail.com").validate().is_err());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), EmailError> {
+        self.recipients()
+            .chain(self.cc.iter().flatten())
+            .chain(self.bcc.iter().flatten())
+            .find(|address| !is_valid_address(address))
+            .map_or(Ok(()), |address| {
+                Err(EmailError::InvalidAddress(address.to_string()))
+            })
+    }
+
+    /// Creates the email options from a `mailto:` URI, as defined by RFC 6068.
+    ///
+    /// The recipients, CC, BCC, subject and body are kept, other headers are ignored.
+    ///
+    /// ```
+    /// use ashpd::desktop::email::EmailOptions;
+    ///
+    /// let options = EmailOptions::from_mailto(
+    ///     "mailto:test@gmail.com?cc=cc@gmail.com&subject=Hello%20there"
+    /// ).unwrap();
+    /// assert_eq!(options.address.as_deref(), Some("test@gmail.com"));
+    /// assert_eq!(options.cc, Some(vec!["cc@gmail.com".to_string()]));
+    /// assert_eq!(options.subject.as_deref(), Some("Hello there"));
+    /// assert_eq!(
+    ///     options.to_mailto(),
+    ///     "mailto:test@gmail.com?cc=cc@gmail.com&subject=Hello%20there"
+    /// );
+    ///
+    /// assert!(EmailOptions::from_mailto("mailtoé").is_err());
+    /// assert!(EmailOptions::from_mailto("é").is_err());
+    /// ```
+    pub fn from_mailto(uri: &str) -> std::result::Result<Self, EmailError> {
+        let invalid = || EmailError::InvalidMailto(uri.to_string());
+        let scheme_len = "mailto:".len();
+        // `get` as `uri` may not have a char boundary where the scheme would end.
+        if !matches!(uri.get(..scheme_len), Some(scheme) if scheme.eq_ignore_ascii_case("mailto:"))
+        {
+            return Err(invalid());
+        }
+        let (to, query) = match uri[scheme_len..].find('?') {
+            Some(index) => (
+                &uri[scheme_len..scheme_len + index],
+                Some(&uri[scheme_len + index + 1..]),
+            ),
+            None => (&uri[scheme_len..], None),
+        };
+        let decode = |value: &str| {
+            percent_decode_str(value)
+                .decode_utf8()
+                .map(|v| v.into_owned())
+                .map_err(|_| invalid())
+        };
+        let split_addresses = |value: &str| -> std::result::Result<Vec<String>, EmailError> {
+            decode(value)?
+                .split(',')
+                .map(str::trim)
+                .filter(|address| !address.is_empty())
+                .map(|address| {
+                    if is_valid_address(address) {
+                        Ok(address.to_string())
+                    } else {
+                        Err(EmailError::InvalidAddress(address.to_string()))
+                    }
+                })
+                .collect()
+        };
+
+        let mut options = Self::default();
+        let mut recipients = split_addresses(to)?;
+        for field in query.into_iter().flat_map(|q| q.split('&')) {
+            let (name, value) = match field.find('=') {
+                Some(index) => (&field[..index], &field[index + 1..]),
+                None => (field, ""),
+            };
+            match decode(name)?.to_ascii_lowercase().as_str() {
+                "to" => recipients.extend(split_addresses(value)?),
+                "cc" => options
+                    .cc
+                    .get_or_insert_with(Vec::new)
+                    .extend(split_addresses(value)?),
+                "bcc" => options
+                    .bcc
+                    .get_or_insert_with(Vec::new)
+                    .extend(split_addresses(value)?),
+                "subject" => options.subject = Some(decode(value)?),
+                "body" => options.body = Some(decode(value)?),
+                _ => (),
+            }
+        }
+        if recipients.len() == 1 {
+            options.address = recipients.pop();
+        } else if !recipients.is_empty() {
+            options.addresses = Some(recipients);
+        }
+        Ok(options)
+    }
+
+    /// Converts the email options to a `mailto:` URI, as defined by RFC 6068.
+    ///
+    /// Attachments can't be part of a `mailto:` URI and are left out.
+    pub fn to_mailto(&self) -> String {
+        let encode = |value: &str| utf8_percent_encode(value, MAILTO_ESCAPE_SET).to_string();
+        let join = |addresses: &[String]| {
+            addresses
+                .iter()
+                .map(|address| encode(address))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let recipients = self.recipients().cloned().collect::<Vec<_>>();
+        let mut fields = vec![];
+        if let Some(ref cc) = self.cc {
+            fields.push(format!("cc={}", join(cc)));
+        }
+        if let Some(ref bcc) = self.bcc {
+            fields.push(format!("bcc={}", join(bcc)));
+        }
+        if let Some(ref subject) = self.subject {
+            fields.push(format!("subject={}", encode(subject)));
+        }
+        if let Some(ref body) = self.body {
+            fields.push(format!("body={}", encode(body)));
+        }
+
+        let mut uri = format!("mailto:{}", join(&recipients));
+        if !fields.is_empty() {
+            uri.push('?');
+            uri.push_str(&fields.join("&"));
+        }
+        uri
+    }
+
+    fn recipients(&self) -> impl Iterator<Item = &String> {
+        self.address.iter().chain(self.addresses.iter().flatten())
+    }
+}
+
+/// Whether `address` is a valid RFC 5322 `addr-spec`, `local-part@domain`.
+fn is_valid_address(address: &str) -> bool {
+    let index = match address.rfind('@') {
+        Some(index) => index,
+        None => return false,
+    };
+    let (local_part, domain) = (&address[..index], &address[index + 1..]);
+
+    let is_valid_local_part =
+        if local_part.len() >= 2 && local_part.starts_with('"') && local_part.ends_with('"') {
+            is_quoted_string(&local_part[1..local_part.len() - 1])
+        } else {
+            is_dot_atom(local_part)
+        };
+    let is_valid_domain = if domain.starts_with('[') && domain.ends_with(']') {
+        domain[1..domain.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_graphic() && !matches!(c, '[' | ']' | '\\'))
+    } else {
+        is_dot_atom(domain)
+    };
+    is_valid_local_part && is_valid_domain
+}
+
+fn is_dot_atom(text: &str) -> bool {
+    let is_atext = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c);
+    !text.is_empty()
+        && text
+            .split('.')
+            .all(|atom| !atom.is_empty() && atom.chars().all(is_atext))
+}
+
+fn is_quoted_string(text: &str) -> bool {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c) if c == ' ' || c == '\t' || c.is_ascii_graphic() => (),
+                _ => return false,
+            },
+            '"' => return false,
+            c if c == ' ' || c == '\t' || c.is_ascii_graphic() => (),
+            _ => return false,
+        }
+    }
+    true
 }
 
 #[dbus_proxy(
@@ -121,7 +473,10 @@ impl EmailOptions {
 )]
 /// The interface lets sandboxed applications request sending an email.
 trait Email {
-    /// Presents a window that lets the user compose an email.
+    /// Presents a window that lets the user compose an email, without validating the addresses,
+    /// see [`EmailProxy::compose_email`].
+    ///
+    /// [`EmailProxy::compose_email`]: ./struct.EmailProxy.html#method.compose_email
     ///
     /// Note that the default email client for the host will need to support mailto: URIs following RFC 2368
     ///
@@ -134,7 +489,8 @@ trait Email {
     ///
     /// [`EmailOptions`]: ./struct.EmailOptions.html
     /// [`RequestProxy`]: ../../request/struct.RequestProxy.html
-    fn compose_email(
+    #[dbus_proxy(name = "ComposeEmail")]
+    fn compose_email_unchecked(
         &self,
         parent_window: WindowIdentifier,
         options: EmailOptions,
@@ -144,3 +500,31 @@ trait Email {
     #[dbus_proxy(property, name = "version")]
     fn version(&self) -> Result<u32>;
 }
+
+impl<'a> EmailProxy<'a> {
+    /// Presents a window that lets the user compose an email.
+    ///
+    /// The addresses are checked with [`EmailOptions::validate`] first,
+    /// an invalid one is reported as an invalid arguments error.
+    ///
+    /// Returns a [`RequestProxy`] object path.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_window` - Identifier for the application window
+    /// * `options` - [`EmailOptions`]
+    ///
+    /// [`EmailOptions::validate`]: ./struct.EmailOptions.html#method.validate
+    /// [`EmailOptions`]: ./struct.EmailOptions.html
+    /// [`RequestProxy`]: ../../request/struct.RequestProxy.html
+    pub fn compose_email(
+        &self,
+        parent_window: WindowIdentifier,
+        options: EmailOptions,
+    ) -> Result<OwnedObjectPath> {
+        options
+            .validate()
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(e.to_string()))?;
+        self.compose_email_unchecked(parent_window, options)
+    }
+}
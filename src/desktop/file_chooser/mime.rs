@@ -0,0 +1,199 @@
+use super::glob_matches;
+use crate::helper::data_home_dir;
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+/// A MIME type known to the file filters, with a description and its file extensions.
+pub(super) struct MimeType {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub extensions: &'static [&'static str],
+}
+
+const fn mime(
+    name: &'static str,
+    description: &'static str,
+    extensions: &'static [&'static str],
+) -> MimeType {
+    MimeType {
+        name,
+        description,
+        extensions,
+    }
+}
+
+/// A subset of the shared MIME-info database, covering the commonly opened files.
+///
+/// It labels the filters, and guesses the MIME types when the database isn't installed.
+/// Other MIME types are labelled with their name.
+const MIME_TYPES: &[MimeType] = &[
+    mime("image/png", "PNG image", &["png"]),
+    mime("image/jpeg", "JPEG image", &["jpg", "jpeg", "jpe"]),
+    mime("image/gif", "GIF image", &["gif"]),
+    mime("image/webp", "WebP image", &["webp"]),
+    mime("image/svg+xml", "SVG image", &["svg"]),
+    mime("image/bmp", "BMP image", &["bmp"]),
+    mime("image/tiff", "TIFF image", &["tif", "tiff"]),
+    mime("image/avif", "AVIF image", &["avif"]),
+    mime("image/heif", "HEIF image", &["heif", "heic"]),
+    mime("image/jxl", "JPEG XL image", &["jxl"]),
+    mime("audio/mpeg", "MP3 audio", &["mp3"]),
+    mime("audio/ogg", "Ogg audio", &["ogg", "oga"]),
+    mime("audio/x-opus+ogg", "Opus audio", &["opus"]),
+    mime("audio/flac", "FLAC audio", &["flac"]),
+    mime("audio/x-wav", "WAV audio", &["wav"]),
+    mime("audio/mp4", "MPEG-4 audio", &["m4a"]),
+    mime("video/mp4", "MPEG-4 video", &["mp4", "m4v"]),
+    mime("video/webm", "WebM video", &["webm"]),
+    mime("video/x-matroska", "Matroska video", &["mkv"]),
+    mime("video/ogg", "Ogg video", &["ogv"]),
+    mime("video/quicktime", "QuickTime video", &["mov", "qt"]),
+    mime("video/x-msvideo", "AVI video", &["avi"]),
+    mime("text/plain", "Plain text document", &["txt", "text"]),
+    mime("text/markdown", "Markdown document", &["md", "markdown"]),
+    mime("text/html", "HTML document", &["html", "htm"]),
+    mime("text/csv", "CSV document", &["csv"]),
+    mime("application/json", "JSON document", &["json"]),
+    mime("application/xml", "XML document", &["xml"]),
+    mime("application/pdf", "PDF document", &["pdf"]),
+    mime("application/zip", "Zip archive", &["zip"]),
+    mime("application/x-tar", "Tar archive", &["tar"]),
+    mime("application/gzip", "Gzip archive", &["gz"]),
+    mime(
+        "application/vnd.oasis.opendocument.text",
+        "ODF text document",
+        &["odt"],
+    ),
+    mime(
+        "application/vnd.oasis.opendocument.spreadsheet",
+        "ODF spreadsheet",
+        &["ods"],
+    ),
+    mime(
+        "application/vnd.oasis.opendocument.presentation",
+        "ODF presentation",
+        &["odp"],
+    ),
+];
+
+/// Describes a MIME type, or a `media/*` wildcard, for a filter label.
+pub(super) fn description(mime_type: &str) -> String {
+    match mime_type {
+        "image/*" => return "Images".to_string(),
+        "audio/*" => return "Audio".to_string(),
+        "video/*" => return "Videos".to_string(),
+        "text/*" => return "Text documents".to_string(),
+        _ => (),
+    }
+    MIME_TYPES
+        .iter()
+        .find(|mime| mime.name == mime_type)
+        .map(|mime| mime.description.to_string())
+        .unwrap_or_else(|| mime_type.to_string())
+}
+
+/// A glob of the shared MIME-info database, from a `globs2` file.
+struct MimeGlob {
+    weight: u32,
+    mime_type: String,
+    pattern: Vec<char>,
+    case_sensitive: bool,
+}
+
+/// The globs of the shared MIME-info database, loaded on first use.
+static GLOBS: Mutex<Option<Arc<Vec<MimeGlob>>>> = Mutex::new(None);
+
+fn globs() -> Arc<Vec<MimeGlob>> {
+    GLOBS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| Arc::new(load_globs()))
+        .clone()
+}
+
+/// Reads the `mime/globs2` files of `$XDG_DATA_HOME` and `$XDG_DATA_DIRS`.
+fn load_globs() -> Vec<MimeGlob> {
+    let data_dirs = match std::env::var_os("XDG_DATA_DIRS") {
+        Some(dirs) if !dirs.is_empty() => dirs,
+        _ => "/usr/local/share:/usr/share".into(),
+    };
+    data_home_dir()
+        .into_iter()
+        .chain(std::env::split_paths(&data_dirs))
+        .filter_map(|dir| fs::read_to_string(dir.join("mime").join("globs2")).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.starts_with('#'))
+                .filter_map(parse_glob)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Parses a `weight:mime/type:glob[:flags]` line.
+fn parse_glob(line: &str) -> Option<MimeGlob> {
+    let mut fields = line.splitn(4, ':');
+    let weight = fields.next()?.parse().ok()?;
+    let mime_type = fields.next()?.to_string();
+    let pattern = fields.next()?;
+    let case_sensitive =
+        matches!(fields.next(), Some(flags) if flags.split(',').any(|flag| flag == "cs"));
+    let pattern = if case_sensitive {
+        pattern.chars().collect()
+    } else {
+        pattern.to_lowercase().chars().collect()
+    };
+    Some(MimeGlob {
+        weight,
+        mime_type,
+        pattern,
+        case_sensitive,
+    })
+}
+
+/// Guesses the MIME type of a file from its name.
+///
+/// The globs of the shared MIME-info database are used, the one with the highest weight,
+/// then the longest pattern, wins. Without the database, the extension is looked up in
+/// the built-in table.
+pub(super) fn from_file_name(name: &str) -> Option<String> {
+    let globs = globs();
+    if globs.is_empty() {
+        let extension = name.rsplit_once('.')?.1;
+        return MIME_TYPES
+            .iter()
+            .find(|mime| {
+                mime.extensions
+                    .iter()
+                    .any(|ext| ext.eq_ignore_ascii_case(extension))
+            })
+            .map(|mime| mime.name.to_string());
+    }
+
+    let name_chars = name.chars().collect::<Vec<_>>();
+    let lowercase_chars = name.to_lowercase().chars().collect::<Vec<_>>();
+    globs
+        .iter()
+        .filter(|glob| {
+            let name = if glob.case_sensitive {
+                &name_chars
+            } else {
+                &lowercase_chars
+            };
+            glob_matches(&glob.pattern, name)
+        })
+        .max_by_key(|glob| (glob.weight, glob.pattern.len()))
+        .map(|glob| glob.mime_type.clone())
+}
+
+/// Whether a MIME type matches a filter's MIME type, which can be a `media/*` wildcard.
+pub(super) fn matches(filter: &str, mime_type: &str) -> bool {
+    match filter.strip_suffix("/*") {
+        Some(media) => matches!(
+            mime_type.split('/').next(),
+            Some(m) if m.eq_ignore_ascii_case(media)
+        ),
+        None => filter.eq_ignore_ascii_case(mime_type),
+    }
+}
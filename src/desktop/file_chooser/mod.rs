@@ -0,0 +1,897 @@
+//! # Examples
+//!
+//! Opening a file
+//! ```no_run
+//! use ashpd::desktop::file_chooser::{
+//!     Choice, FileChooserProxy, FileFilter, SelectedFiles, OpenFileOptions,
+//! };
+//! use ashpd::{RequestProxy, Response, WindowIdentifier};
+//! use zbus::{fdo::Result, Connection};
+//!
+//! fn main() -> Result<()> {
+//!     let connection = Connection::new_session()?;
+//!
+//!     let encoding = Choice::combo_box("encoding", "Encoding", "latin15")
+//!         .insert("utf8", "Unicode (UTF-8)")
+//!         .insert("latin15", "Western");
+//!     let re_encode = Choice::checkbox("re-encode", "Re-encode", false);
+//!
+//!     let proxy = FileChooserProxy::new(&connection)?;
+//!     let request_handle = proxy.open_file(
+//!         WindowIdentifier::default(),
+//!         "open a file to read",
+//!         OpenFileOptions::default()
+//!             .accept_label("read")
+//!             .modal(true)
+//!             .multiple(true)
+//!             .choice(encoding.clone())
+//!             .choice(re_encode.clone())
+//!             .filter(FileFilter::new("SVG Image").mimetype("image/svg+xml")),
+//!     )?;
+//!
+//!     let request = RequestProxy::new(&connection, &request_handle)?;
+//!     request.on_response(move |r: Response<SelectedFiles>| {
+//!         let files = r.unwrap();
+//!         println!("{:#?}", files.paths());
+//!         println!("encoding: {:?}", files.choice(&encoding));
+//!         println!("re-encode: {:?}", files.choice(&re_encode));
+//!     })?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Ask to save a file
+//!
+//! ```no_run
+//! use ashpd::desktop::file_chooser::{
+//!     FileChooserProxy, FileFilter, SelectedFiles, SaveFileOptions,
+//! };
+//! use ashpd::{RequestProxy, Response, WindowIdentifier};
+//! use zbus::{fdo::Result, Connection};
+//!
+//! fn main() -> Result<()> {
+//!     let connection = Connection::new_session()?;
+//!
+//!     let proxy = FileChooserProxy::new(&connection)?;
+//!     let request_handle = proxy.save_file(
+//!         WindowIdentifier::default(),
+//!         "open a file to write",
+//!         SaveFileOptions::default()
+//!             .accept_label("write")
+//!             .current_name("image.jpg")
+//!             .modal(true)
+//!             .filter(FileFilter::new("JPEG Image").glob("*.jpg")),
+//!     )?;
+//!
+//!     let request = RequestProxy::new(&connection, &request_handle)?;
+//!     request.on_response(|r: Response<SelectedFiles>| {
+//!         println!("{:#?}", r.unwrap());
+//!     })?;
+//!
+//!     Ok(())
+//! }
+//!```
+//!
+//! Ask to save multiple files
+//! ```no_run
+//! use ashpd::desktop::file_chooser::{FileChooserProxy, SaveFilesOptions, SelectedFiles};
+//! use ashpd::{RequestProxy, Response, WindowIdentifier};
+//! use zbus::{fdo::Result, Connection};
+//!
+//! fn main() -> Result<()> {
+//!     let connection = Connection::new_session()?;
+//!
+//!     let proxy = FileChooserProxy::new(&connection)?;
+//!     let request_handle = proxy.save_files(
+//!         WindowIdentifier::default(),
+//!         "open files to write",
+//!         SaveFilesOptions::default()
+//!             .accept_label("write files")
+//!             .modal(true)
+//!             .current_folder("/home/bilelmoussaoui/Pictures")
+//!             .files(vec!["test.jpg".to_string(), "awesome.png".to_string()]),
+//!     )?;
+//!
+//!     let request = RequestProxy::new(&connection, &request_handle)?;
+//!     request.on_response(|r: Response<SelectedFiles>| {
+//!         println!("{:#?}", r.unwrap());
+//!     })?;
+//!
+//!     Ok(())
+//! }
+//! ```
+mod mime;
+
+use crate::{HandleToken, NString, WindowIdentifier};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::path::{Path, PathBuf};
+use url::Url;
+use zbus::{dbus_proxy, fdo::Result};
+use zvariant::{OwnedObjectPath, Signature};
+use zvariant_derive::{DeserializeDict, SerializeDict, Type, TypeDict};
+
+#[derive(Serialize, Deserialize, Type, Debug, Clone, PartialEq)]
+/// A file filter, to limit the available file choices to a mimetype or a glob pattern.
+pub struct FileFilter(String, Vec<(FilterType, String)>);
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug, Type, Clone, Copy)]
+#[repr(u32)]
+enum FilterType {
+    GlobPattern = 0,
+    MimeType = 1,
+}
+
+impl FileFilter {
+    /// Create a new file filter
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - user-visible name of the file filter.
+    pub fn new(label: &str) -> Self {
+        Self(label.to_string(), vec![])
+    }
+
+    /// Adds a mime type to the file filter.
+    pub fn mimetype(mut self, mimetype: &str) -> Self {
+        self.1.push((FilterType::MimeType, mimetype.to_string()));
+        self
+    }
+
+    /// Adds a glob pattern to the file filter.
+    pub fn glob(mut self, pattern: &str) -> Self {
+        self.1.push((FilterType::GlobPattern, pattern.to_string()));
+        self
+    }
+
+    /// The user-visible name of the file filter.
+    pub fn label(&self) -> &str {
+        &self.0
+    }
+
+    /// A filter for any image.
+    pub fn images() -> Self {
+        Self::new(&mime::description("image/*")).mimetype("image/*")
+    }
+
+    /// A filter for any audio file.
+    pub fn audio() -> Self {
+        Self::new(&mime::description("audio/*")).mimetype("audio/*")
+    }
+
+    /// A filter for any video.
+    pub fn videos() -> Self {
+        Self::new(&mime::description("video/*")).mimetype("video/*")
+    }
+
+    /// A filter for any text document.
+    pub fn text() -> Self {
+        Self::new(&mime::description("text/*")).mimetype("text/*")
+    }
+
+    /// Creates a filter for a list of MIME types, labelled after their descriptions.
+    ///
+    /// ```
+    /// use ashpd::desktop::file_chooser::FileFilter;
+    ///
+    /// let filter = FileFilter::from_mime_types(&["image/png", "image/jpeg"]);
+    /// assert_eq!(filter.label(), "PNG image, JPEG image");
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_types` - The MIME types, which can be wildcards like `image/*`.
+    pub fn from_mime_types(mime_types: &[&str]) -> Self {
+        let label = mime_types
+            .iter()
+            .map(|mime_type| mime::description(mime_type))
+            .collect::<Vec<_>>()
+            .join(", ");
+        mime_types
+            .iter()
+            .fold(Self::new(&label), |filter, mime_type| {
+                filter.mimetype(mime_type)
+            })
+    }
+
+    /// Creates a filter for a list of file extensions, matched case-insensitively.
+    ///
+    /// ```
+    /// use ashpd::desktop::file_chooser::FileFilter;
+    /// use std::path::Path;
+    ///
+    /// let filter = FileFilter::from_extensions(&["jpg", "png"]);
+    /// assert_eq!(filter.label(), "JPG, PNG files");
+    /// assert!(filter.matches(Path::new("/home/bilelmoussaoui/Pictures/photo.JPG")));
+    /// assert!(!filter.matches(Path::new("/home/bilelmoussaoui/Pictures/photo.gif")));
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `extensions` - The file extensions, without the leading dot.
+    pub fn from_extensions(extensions: &[&str]) -> Self {
+        let label = format!(
+            "{} files",
+            extensions
+                .iter()
+                .map(|extension| extension.to_uppercase())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        extensions
+            .iter()
+            .fold(Self::new(&label), |filter, extension| {
+                filter.glob_case_insensitive(&format!("*.{}", extension))
+            })
+    }
+
+    /// Adds a glob pattern to the file filter, matching both cases of its letters.
+    ///
+    /// For example, `*.jpg` is added as `*.[jJ][pP][gG]`.
+    pub fn glob_case_insensitive(self, pattern: &str) -> Self {
+        let mut case_insensitive = String::with_capacity(pattern.len() * 4);
+        let mut in_class = false;
+        for c in pattern.chars() {
+            match c {
+                '[' => in_class = true,
+                ']' => in_class = false,
+                _ => (),
+            }
+            if !in_class && c.is_alphabetic() && c.to_lowercase().ne(c.to_uppercase()) {
+                case_insensitive.push('[');
+                case_insensitive.extend(c.to_lowercase());
+                case_insensitive.extend(c.to_uppercase());
+                case_insensitive.push(']');
+            } else {
+                case_insensitive.push(c);
+            }
+        }
+        self.glob(&case_insensitive)
+    }
+
+    /// Whether a file satisfies the filter, for example the `current_filter` the user ended on.
+    ///
+    /// Glob patterns are matched against the file name, MIME types are guessed from
+    /// the file name with the globs of the shared MIME-info database, read from
+    /// `$XDG_DATA_HOME/mime` and `$XDG_DATA_DIRS/mime`.
+    ///
+    /// The file content isn't looked at, and neither are the parent types of a MIME type,
+    /// so a `text/markdown` file doesn't match a `text/plain` filter.
+    /// Without the database, only a built-in table of about thirty common image, audio,
+    /// video, text and document types is known, and files of other types never match
+    /// a MIME type filter.
+    pub fn matches(&self, path: &Path) -> bool {
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy(),
+            None => return false,
+        };
+        let mime_type = mime::from_file_name(&name);
+        self.1
+            .iter()
+            .any(|(filter_type, filter)| match filter_type {
+                FilterType::GlobPattern => glob_matches(
+                    &filter.chars().collect::<Vec<_>>(),
+                    &name.chars().collect::<Vec<_>>(),
+                ),
+                FilterType::MimeType => {
+                    matches!(mime_type, Some(ref mime_type) if mime::matches(filter, mime_type))
+                }
+            })
+    }
+}
+
+/// A parsed element of a glob pattern.
+enum GlobToken<'a> {
+    Star,
+    Any,
+    Class { class: &'a [char], negated: bool },
+    Literal(char),
+}
+
+impl GlobToken<'_> {
+    /// Whether a single character of the name matches the token, other than a `*`.
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Star => false,
+            Self::Any => true,
+            Self::Class { class, negated } => {
+                let mut matched = false;
+                let mut i = 0;
+                while i < class.len() {
+                    if i + 2 < class.len() && class[i + 1] == '-' {
+                        matched |= class[i] <= c && c <= class[i + 2];
+                        i += 3;
+                    } else {
+                        matched |= class[i] == c;
+                        i += 1;
+                    }
+                }
+                matched != *negated
+            }
+            Self::Literal(literal) => *literal == c,
+        }
+    }
+}
+
+fn glob_tokens(pattern: &[char]) -> Vec<GlobToken<'_>> {
+    let mut tokens = Vec::with_capacity(pattern.len());
+    let mut i = 0;
+    while i < pattern.len() {
+        match pattern[i] {
+            '*' => tokens.push(GlobToken::Star),
+            '?' => tokens.push(GlobToken::Any),
+            '[' => {
+                let rest = &pattern[i + 1..];
+                let negated = matches!(rest.first(), Some('!') | Some('^'));
+                let class = if negated { &rest[1..] } else { rest };
+                // A `]` right after the opening bracket is part of the class.
+                match class.iter().skip(1).position(|&c| c == ']') {
+                    Some(end) => {
+                        tokens.push(GlobToken::Class {
+                            class: &class[..end + 1],
+                            negated,
+                        });
+                        i += negated as usize + end + 2;
+                    }
+                    // Without a closing bracket, it's a literal `[`.
+                    None => tokens.push(GlobToken::Literal('[')),
+                }
+            }
+            c => tokens.push(GlobToken::Literal(c)),
+        }
+        i += 1;
+    }
+    tokens
+}
+
+/// Matches a shell glob pattern, supporting `*`, `?` and `[...]` character classes.
+///
+/// On a mismatch, only the last `*` is retried one character further,
+/// which keeps the matching linear in the length of the pattern times the name.
+fn glob_matches(pattern: &[char], name: &[char]) -> bool {
+    let tokens = glob_tokens(pattern);
+    let (mut t, mut n) = (0, 0);
+    // The position of the last `*` and of the name it was tried at.
+    let mut backtrack = None;
+    while n < name.len() {
+        match tokens.get(t) {
+            Some(GlobToken::Star) => {
+                backtrack = Some((t, n));
+                t += 1;
+                continue;
+            }
+            Some(token) if token.matches(name[n]) => {
+                t += 1;
+                n += 1;
+                continue;
+            }
+            _ => (),
+        }
+        match backtrack {
+            Some((star, star_n)) => {
+                backtrack = Some((star, star_n + 1));
+                t = star + 1;
+                n = star_n + 1;
+            }
+            None => return false,
+        }
+    }
+    tokens[t..]
+        .iter()
+        .all(|token| matches!(token, GlobToken::Star))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How a [`Choice`] is presented to the user.
+///
+/// [`Choice`]: ./struct.Choice.html
+pub enum ChoiceKind {
+    /// A combo box, to select one of the options of the choice.
+    ComboBox,
+    /// A checkbox, its selection is either `true` or `false`.
+    Checkbox,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Presents the user with a choice to select from, as a combo box, or as a checkbox.
+///
+/// On the bus, a checkbox is a choice without any options, so a combo box without options
+/// is received back as a checkbox.
+pub struct Choice {
+    id: String,
+    label: String,
+    kind: ChoiceKind,
+    options: Vec<(String, String)>,
+    initial_selection: String,
+}
+
+impl Choice {
+    /// Creates a new choice shown as a combo box.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A unique identifier of the choice
+    /// * `label` - user-visible name of the choice
+    /// * `initial_selection` - the key of the initially selected option
+    pub fn new(id: &str, label: &str, initial_selection: &str) -> Self {
+        Self::combo_box(id, label, initial_selection)
+    }
+
+    /// Creates a new choice shown as a combo box.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A unique identifier of the choice
+    /// * `label` - user-visible name of the choice
+    /// * `initial_selection` - the key of the initially selected option
+    pub fn combo_box(id: &str, label: &str, initial_selection: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            kind: ChoiceKind::ComboBox,
+            options: vec![],
+            initial_selection: initial_selection.to_string(),
+        }
+    }
+
+    /// Creates a new choice shown as a checkbox.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A unique identifier of the choice
+    /// * `label` - user-visible name of the choice
+    /// * `checked` - whether the checkbox is initially checked
+    pub fn checkbox(id: &str, label: &str, checked: bool) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            kind: ChoiceKind::Checkbox,
+            options: vec![],
+            initial_selection: checked.to_string(),
+        }
+    }
+
+    /// Adds a (key, value) option to a combo box.
+    ///
+    /// A checkbox has no options, this has no effect on it.
+    pub fn insert(mut self, key: &str, value: &str) -> Self {
+        if self.kind == ChoiceKind::ComboBox {
+            self.options.push((key.to_string(), value.to_string()));
+        }
+        self
+    }
+
+    /// The choice's unique id
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// The user visible label of the choice.
+    pub fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    /// Whether the choice is shown as a combo box or as a checkbox.
+    pub fn kind(&self) -> ChoiceKind {
+        self.kind
+    }
+
+    /// The initially selected value.
+    pub fn initial_selection(&self) -> String {
+        self.initial_selection.clone()
+    }
+
+    /// The (key, value) options of a combo box.
+    pub fn options(&self) -> &[(String, String)] {
+        &self.options
+    }
+}
+
+impl zvariant::Type for Choice {
+    fn signature() -> Signature<'static> {
+        <(String, String, Vec<(String, String)>, String)>::signature()
+    }
+}
+
+impl Serialize for Choice {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (
+            &self.id,
+            &self.label,
+            &self.options,
+            &self.initial_selection,
+        )
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Choice {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (id, label, options, initial_selection) =
+            <(String, String, Vec<(String, String)>, String)>::deserialize(deserializer)?;
+        let kind = if options.is_empty() {
+            ChoiceKind::Checkbox
+        } else {
+            ChoiceKind::ComboBox
+        };
+        Ok(Self {
+            id,
+            label,
+            kind,
+            options,
+            initial_selection,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The value the user selected for a [`Choice`].
+///
+/// [`Choice`]: ./struct.Choice.html
+pub enum ChoiceSelection<'a> {
+    /// The key of the option selected in a combo box.
+    ComboBox(&'a str),
+    /// Whether a checkbox was checked.
+    Checkbox(bool),
+}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options for a `open_file` request.
+pub struct OpenFileOptions {
+    /// A string that will be used as the last element of the handle.
+    pub handle_token: Option<HandleToken>,
+    /// Label for the accept button. Mnemonic underlines are allowed.
+    pub accept_label: Option<String>,
+    /// Whether the dialog should be modal.
+    pub modal: Option<bool>,
+    /// Whether multiple files can be selected or not.
+    pub multiple: Option<bool>,
+    /// Whether to select for folders instead of files.
+    pub directory: Option<bool>,
+    /// List of serialized file filters.
+    pub filters: Vec<FileFilter>,
+    /// Request that this filter be set by default at dialog creation.
+    pub current_filter: Option<FileFilter>,
+    /// List of serialized combo boxes to add to the file chooser
+    pub choices: Vec<Choice>,
+}
+
+impl OpenFileOptions {
+    /// Sets the handle token.
+    pub fn handle_token(mut self, handle_token: HandleToken) -> Self {
+        self.handle_token = Some(handle_token);
+        self
+    }
+
+    /// Sets a user-visible string to the "accept" button.
+    pub fn accept_label(mut self, accept_label: &str) -> Self {
+        self.accept_label = Some(accept_label.to_string());
+        self
+    }
+
+    /// Sets whether the dialog should be a modal.
+    pub fn modal(mut self, modal: bool) -> Self {
+        self.modal = Some(modal);
+        self
+    }
+
+    /// Sets whether to allow multiple files selection.
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = Some(multiple);
+        self
+    }
+
+    /// Sets whether to select directories or not.
+    pub fn directory(mut self, directory: bool) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+
+    /// Adds a files filter.
+    pub fn filter(mut self, filter: FileFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Specifies the default filter.
+    pub fn current_filter(mut self, current_filter: FileFilter) -> Self {
+        self.current_filter = Some(current_filter);
+        self
+    }
+
+    /// Adds a choice.
+    pub fn choice(mut self, choice: Choice) -> Self {
+        self.choices.push(choice);
+        self
+    }
+}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options for a save file request.
+pub struct SaveFileOptions {
+    /// A string that will be used as the last element of the handle.
+    pub handle_token: Option<HandleToken>,
+    /// Label for the accept button. Mnemonic underlines are allowed.
+    pub accept_label: Option<String>,
+    /// Whether the dialog should be modal.
+    pub modal: Option<bool>,
+    /// Suggested filename.
+    pub current_name: Option<String>,
+    /// Suggested folder to save the file in.
+    pub current_folder: Option<NString>,
+    /// The current file (when saving an existing file).
+    pub current_file: Option<NString>,
+    /// List of serialized file filters.
+    pub filters: Vec<FileFilter>,
+    /// Request that this filter be set by default at dialog creation.
+    pub current_filter: Option<FileFilter>,
+    /// List of serialized combo boxes to add to the file chooser
+    pub choices: Vec<Choice>,
+}
+
+impl SaveFileOptions {
+    /// Sets the handle token.
+    pub fn handle_token(mut self, handle_token: HandleToken) -> Self {
+        self.handle_token = Some(handle_token);
+        self
+    }
+
+    /// Sets a user-visible string to the "accept" button.
+    pub fn accept_label(mut self, accept_label: &str) -> Self {
+        self.accept_label = Some(accept_label.to_string());
+        self
+    }
+
+    /// Sets the current file name.
+    pub fn current_name(mut self, current_name: &str) -> Self {
+        self.current_name = Some(current_name.to_string());
+        self
+    }
+
+    /// Sets the current folder.
+    pub fn current_folder(mut self, current_folder: &str) -> Self {
+        self.current_folder = Some(current_folder.into());
+        self
+    }
+
+    /// Sets the absolute path of the file.
+    pub fn current_file(mut self, current_file: &str) -> Self {
+        self.current_file = Some(current_file.into());
+        self
+    }
+
+    /// Sets whether the dialog should be a modal.
+    pub fn modal(mut self, modal: bool) -> Self {
+        self.modal = Some(modal);
+        self
+    }
+
+    /// Adds a files filter.
+    pub fn filter(mut self, filter: FileFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Sets the default filter.
+    pub fn current_filter(mut self, current_filter: FileFilter) -> Self {
+        self.current_filter = Some(current_filter);
+        self
+    }
+
+    /// Adds a choice.
+    pub fn choice(mut self, choice: Choice) -> Self {
+        self.choices.push(choice);
+        self
+    }
+}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options for a save files request.
+pub struct SaveFilesOptions {
+    /// A string that will be used as the last element of the handle.
+    pub handle_token: Option<HandleToken>,
+    /// Label for the accept button. Mnemonic underlines are allowed.
+    pub accept_label: Option<String>,
+    /// Whether the dialog should be modal.
+    pub modal: Option<bool>,
+    /// List of serialized combo boxes to add to the file chooser
+    pub choices: Vec<Choice>,
+    /// Suggested folder to save the file in.
+    pub current_folder: Option<NString>,
+    /// An array of file names to be saved.
+    pub files: Option<Vec<NString>>,
+}
+
+impl SaveFilesOptions {
+    /// Sets the handle token.
+    pub fn handle_token(mut self, handle_token: HandleToken) -> Self {
+        self.handle_token = Some(handle_token);
+        self
+    }
+
+    /// Sets a user-visible string to the "accept" button.
+    pub fn accept_label(mut self, accept_label: &str) -> Self {
+        self.accept_label = Some(accept_label.to_string());
+        self
+    }
+
+    /// Sets whether the dialog should be a modal.
+    pub fn modal(mut self, modal: bool) -> Self {
+        self.modal = Some(modal);
+        self
+    }
+
+    /// Adds a choice.
+    pub fn choice(mut self, choice: Choice) -> Self {
+        self.choices.push(choice);
+        self
+    }
+
+    /// Specifies the current folder path.
+    pub fn current_folder(mut self, current_folder: &str) -> Self {
+        self.current_folder = Some(current_folder.into());
+        self
+    }
+
+    /// Sets a list of files to save.
+    pub fn files(mut self, files: Vec<String>) -> Self {
+        self.files = Some(
+            files
+                .into_iter()
+                .map(|f| f.into())
+                .collect::<Vec<NString>>(),
+        );
+        self
+    }
+}
+
+#[derive(Debug, TypeDict, SerializeDict, DeserializeDict)]
+/// A response to an open/save file request.
+pub struct SelectedFiles {
+    /// The selected files uris.
+    pub uris: Vec<String>,
+    /// The selected value of each choice as a tuple of (key, value)
+    pub choices: Option<Vec<(String, String)>>,
+    /// The filter that was selected when the user accepted the dialog.
+    pub current_filter: Option<FileFilter>,
+}
+
+impl SelectedFiles {
+    /// The local paths of the selected files.
+    ///
+    /// The URIs are percent-decoded to the exact bytes of the path, which don't have to be valid UTF-8.
+    /// URIs that don't point to a local file are skipped.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.uris
+            .iter()
+            .filter_map(|uri| Url::parse(uri).ok())
+            .filter(|url| url.scheme() == "file")
+            .filter_map(|url| url.to_file_path().ok())
+            .collect()
+    }
+
+    /// The value selected for a choice of the request, typed after its [`ChoiceKind`].
+    ///
+    /// Returns `None` if the choice isn't part of the response,
+    /// or if the selection of a checkbox is neither `true` nor `false`.
+    ///
+    /// ```
+    /// use ashpd::desktop::file_chooser::{Choice, ChoiceSelection, SelectedFiles};
+    ///
+    /// let encoding = Choice::combo_box("encoding", "Encoding", "latin15")
+    ///     .insert("utf8", "Unicode (UTF-8)")
+    ///     .insert("latin15", "Western");
+    /// let re_encode = Choice::checkbox("re-encode", "Re-encode", false);
+    ///
+    /// let files = SelectedFiles {
+    ///     uris: vec![],
+    ///     choices: Some(vec![
+    ///         ("encoding".to_string(), "utf8".to_string()),
+    ///         ("re-encode".to_string(), "true".to_string()),
+    ///     ]),
+    ///     current_filter: None,
+    /// };
+    /// assert_eq!(files.choice(&encoding), Some(ChoiceSelection::ComboBox("utf8")));
+    /// assert_eq!(files.choice(&re_encode), Some(ChoiceSelection::Checkbox(true)));
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `choice` - The choice, as passed to the request
+    ///
+    /// [`ChoiceKind`]: ./enum.ChoiceKind.html
+    pub fn choice<'a>(&'a self, choice: &Choice) -> Option<ChoiceSelection<'a>> {
+        let selection = self
+            .choices
+            .as_ref()?
+            .iter()
+            .find(|(choice_id, _)| *choice_id == choice.id)
+            .map(|(_, selection)| selection.as_str())?;
+        match choice.kind {
+            ChoiceKind::ComboBox => Some(ChoiceSelection::ComboBox(selection)),
+            ChoiceKind::Checkbox => selection.parse().ok().map(ChoiceSelection::Checkbox),
+        }
+    }
+}
+
+#[dbus_proxy(
+    interface = "org.freedesktop.portal.FileChooser",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop"
+)]
+/// The interface lets sandboxed applications ask the user for access to files outside the sandbox.
+/// The portal backend will present the user with a file chooser dialog.
+trait FileChooser {
+    /// Asks to open one or more files.
+    ///
+    /// Returns a [`RequestProxy`] object path.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_window` - Identifier for the application window
+    /// * `title` - Title for the file chooser dialog
+    /// * `options` - [`OpenFileOptions`]
+    ///
+    /// [`OpenFileOptions`]: ./struct.OpenFileOptions.html
+    /// [`RequestProxy`]: ../request/struct.RequestProxy.html
+    fn open_file(
+        &self,
+        parent_window: WindowIdentifier,
+        title: &str,
+        options: OpenFileOptions,
+    ) -> Result<OwnedObjectPath>;
+
+    /// Asks for a location to save a file.
+    ///
+    /// Returns a [`RequestProxy`] object path.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_window` - Identifier for the application window
+    /// * `title` - Title for the file chooser dialog
+    /// * `options` - [`SaveFileOptions`]
+    ///
+    /// [`SaveFileOptions`]: ./struct.SaveFileOptions.html
+    /// [`RequestProxy`]: ../request/struct.RequestProxy.html
+    fn save_file(
+        &self,
+        parent_window: WindowIdentifier,
+        title: &str,
+        options: SaveFileOptions,
+    ) -> Result<OwnedObjectPath>;
+
+    /// Asks for a folder as a location to save one or more files.
+    /// The names of the files will be used as-is and appended to the
+    /// selected folder's path in the list of returned files.
+    /// If the selected folder already contains a file with one of the given
+    /// names, the portal may prompt or take some other action to
+    /// construct a unique file name and return that instead.
+    ///
+    /// Returns a [`RequestProxy`] object path.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_window` - Identifier for the application window
+    /// * `title` - Title for the file chooser dialog
+    /// * `options` - [`SaveFilesOptions`]
+    ///
+    /// [`SaveFilesOptions`]: ./struct.SaveFilesOptions.html
+    /// [`RequestProxy`]: ../request/struct.RequestProxy.html
+    fn save_files(
+        &self,
+        parent_window: WindowIdentifier,
+        title: &str,
+        options: SaveFilesOptions,
+    ) -> Result<OwnedObjectPath>;
+
+    /// version property
+    #[dbus_proxy(property, name = "version")]
+    fn version(&self) -> Result<u32>;
+}
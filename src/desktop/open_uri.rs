@@ -34,7 +34,7 @@
 //! Open a file from a URI
 //!
 //! ```no_run
-//! use ashpd::desktop::open_uri::{OpenFileOptions, OpenURIProxy};
+//! use ashpd::desktop::open_uri::{OpenURIOptions, OpenURIProxy};
 //! use ashpd::{RequestProxy, Response, BasicResponse as Basic, WindowIdentifier};
 //! use zbus::{self, fdo::Result};
 //!
@@ -45,7 +45,7 @@
 //!     let request_handle = proxy.open_uri(
 //!         WindowIdentifier::default(),
 //!         "file:///home/bilelmoussaoui/Downloads/adwaita-night.jpg",
-//!         OpenFileOptions::default(),
+//!         OpenURIOptions::default(),
 //!     )?;
 //!
 //!     let request = RequestProxy::new(&connection, &request_handle)?;
@@ -56,7 +56,41 @@
 //!     Ok(())
 //! }
 //! ```
-use crate::{HandleToken, WindowIdentifier};
+//!
+//! Open a file, a website or show a file in the file manager directly
+//!
+//! ```no_run
+//! use ashpd::desktop::open_uri::{self, OpenDirOptions, OpenFileOptions, OpenURIOptions};
+//! use ashpd::WindowIdentifier;
+//! use std::path::Path;
+//! use url::Url;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     let path = Path::new("/home/bilelmoussaoui/Downloads/adwaita-night.jpg");
+//!
+//!     open_uri::open_path(
+//!         WindowIdentifier::default(),
+//!         path,
+//!         OpenFileOptions::default().ask(true),
+//!     )?;
+//!     open_uri::show_in_folder(
+//!         WindowIdentifier::default(),
+//!         path,
+//!         OpenDirOptions::default().activation_token("startup-token"),
+//!     )?;
+//!
+//!     let url = Url::parse("https://github.com/bilelmoussaoui/ashpd").unwrap();
+//!     open_uri::open_url(WindowIdentifier::default(), url, OpenURIOptions::default())?;
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::helper::open_o_path;
+use crate::{BasicResponse, Error, HandleToken, RequestProxy, WindowIdentifier};
+use std::fs::OpenOptions;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use url::Url;
 use zbus::{dbus_proxy, fdo::Result};
 use zvariant::{Fd, OwnedObjectPath};
 use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
@@ -66,6 +100,8 @@ use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
 pub struct OpenDirOptions {
     /// A string that will be used as the last element of the handle.
     pub handle_token: Option<HandleToken>,
+    /// A token that can be used to activate the chosen application.
+    pub activation_token: Option<String>,
 }
 
 impl OpenDirOptions {
@@ -74,6 +110,14 @@ impl OpenDirOptions {
         self.handle_token = Some(handle_token);
         self
     }
+
+    /// Sets the token used to activate the file manager, see [`OpenURIOptions::activation_token`].
+    ///
+    /// [`OpenURIOptions::activation_token`]: ./struct.OpenURIOptions.html#method.activation_token
+    pub fn activation_token(mut self, activation_token: &str) -> Self {
+        self.activation_token = Some(activation_token.to_string());
+        self
+    }
 }
 
 #[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
@@ -86,6 +130,8 @@ pub struct OpenFileOptions {
     pub writeable: Option<bool>,
     /// Whether to ask the user to choose an app. If this is not passed, or false, the portal may use a default or pick the last choice.
     pub ask: Option<bool>,
+    /// A token that can be used to activate the chosen application.
+    pub activation_token: Option<String>,
 }
 
 impl OpenFileOptions {
@@ -106,6 +152,49 @@ impl OpenFileOptions {
         self.ask = Some(ask);
         self
     }
+
+    /// Sets the token used to activate the chosen application, see [`OpenURIOptions::activation_token`].
+    ///
+    /// [`OpenURIOptions::activation_token`]: ./struct.OpenURIOptions.html#method.activation_token
+    pub fn activation_token(mut self, activation_token: &str) -> Self {
+        self.activation_token = Some(activation_token.to_string());
+        self
+    }
+}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options for an open URI request.
+pub struct OpenURIOptions {
+    /// A string that will be used as the last element of the handle.
+    pub handle_token: Option<HandleToken>,
+    /// Whether to ask the user to choose an app. If this is not passed, or false, the portal may use a default or pick the last choice.
+    pub ask: Option<bool>,
+    /// A token that can be used to activate the chosen application.
+    pub activation_token: Option<String>,
+}
+
+impl OpenURIOptions {
+    /// Sets the handle token.
+    pub fn handle_token(mut self, handle_token: HandleToken) -> Self {
+        self.handle_token = Some(handle_token);
+        self
+    }
+
+    /// Whether to always ask the user which application to use or not.
+    pub fn ask(mut self, ask: bool) -> Self {
+        self.ask = Some(ask);
+        self
+    }
+
+    /// Sets the token used to activate the chosen application.
+    ///
+    /// On Wayland, an application is only allowed to take the focus if it's given
+    /// an activation token obtained with the `xdg_activation_v1` protocol,
+    /// typically by the application triggering the request.
+    pub fn activation_token(mut self, activation_token: &str) -> Self {
+        self.activation_token = Some(activation_token.to_string());
+        self
+    }
 }
 
 #[dbus_proxy(
@@ -154,7 +243,7 @@ trait OpenURI {
         options: OpenFileOptions,
     ) -> Result<OwnedObjectPath>;
 
-    /// Asks to open a local file.
+    /// Asks to open a URI.
     ///
     /// Returns a [`RequestProxy`] object path.
     ///
@@ -162,19 +251,106 @@ trait OpenURI {
     ///
     /// * `parent_window` - Identifier for the application window
     /// * `uri` - The uri to open
-    /// * `options` - [`OpenFileOptions`]
+    /// * `options` - [`OpenURIOptions`]
     ///
-    /// [`OpenFileOptions`]: ./struct.OpenFileOptions.html
+    /// [`OpenURIOptions`]: ./struct.OpenURIOptions.html
     /// [`RequestProxy`]: ../request/struct.RequestProxy.html
     #[dbus_proxy(name = "OpenURI")]
     fn open_uri(
         &self,
         parent_window: WindowIdentifier,
         uri: &str,
-        options: OpenFileOptions,
+        options: OpenURIOptions,
     ) -> Result<OwnedObjectPath>;
 
     /// version property
     #[dbus_proxy(property, name = "version")]
     fn version(&self) -> Result<u32>;
 }
+
+/// Asks to open a local file with the user's preferred application.
+///
+/// The file is opened for writing when [`OpenFileOptions::writeable`] is set,
+/// which requires the caller to be allowed to write to it.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `path` - The path of the file to open
+/// * `options` - [`OpenFileOptions`]
+///
+/// [`OpenFileOptions`]: ./struct.OpenFileOptions.html
+/// [`OpenFileOptions::writeable`]: ./struct.OpenFileOptions.html#method.writeable
+pub fn open_path(
+    parent_window: WindowIdentifier,
+    path: &Path,
+    options: OpenFileOptions,
+) -> crate::Result<()> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = OpenURIProxy::new(&connection)?;
+    // The portal only grants write access to a file opened for writing, not through O_PATH.
+    let file = if options.writeable == Some(true) && !path.is_dir() {
+        OpenOptions::new().read(true).write(true).open(path)?
+    } else {
+        open_o_path(path)?
+    };
+    let handle = proxy.open_file(parent_window, Fd::from(file.as_raw_fd()), options)?;
+    let request = RequestProxy::new(&connection, &handle)?;
+    request.receive_response::<BasicResponse>()??;
+    Ok(())
+}
+
+/// Asks to open the directory containing a local file in the file manager.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `path` - The path of the file to show
+/// * `options` - [`OpenDirOptions`]
+///
+/// [`OpenDirOptions`]: ./struct.OpenDirOptions.html
+pub fn show_in_folder(
+    parent_window: WindowIdentifier,
+    path: &Path,
+    options: OpenDirOptions,
+) -> crate::Result<()> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = OpenURIProxy::new(&connection)?;
+    let file = open_o_path(path)?;
+    let handle = proxy.open_directory(parent_window, Fd::from(file.as_raw_fd()), options)?;
+    let request = RequestProxy::new(&connection, &handle)?;
+    request.receive_response::<BasicResponse>()??;
+    Ok(())
+}
+
+/// Asks to open a URL, e.g. a website, with the user's preferred application.
+///
+/// Local files are rejected, they have to be opened with [`open_path`] instead
+/// as the portal needs a file descriptor to check whether the application has access to them.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `url` - The URL to open
+/// * `options` - [`OpenURIOptions`]
+///
+/// [`open_path`]: ./fn.open_path.html
+/// [`OpenURIOptions`]: ./struct.OpenURIOptions.html
+pub fn open_url(
+    parent_window: WindowIdentifier,
+    url: Url,
+    options: OpenURIOptions,
+) -> crate::Result<()> {
+    if url.scheme() == "file" {
+        return Err(Error::InvalidArgument(format!(
+            "{} is a local file, use open_path instead",
+            url
+        )));
+    }
+    let connection = zbus::Connection::new_session()?;
+    let proxy = OpenURIProxy::new(&connection)?;
+    let handle = proxy.open_uri(parent_window, url.as_str(), options)?;
+    let request = RequestProxy::new(&connection, &handle)?;
+    request.receive_response::<BasicResponse>()??;
+    Ok(())
+}
@@ -0,0 +1,1265 @@
+//! # Examples
+//!
+//! Print a file
+//!
+//! ```no_run
+//! use ashpd::desktop::print::{PrintOptions, PrintProxy};
+//! use zbus::{fdo::Result, Connection};
+//! use ashpd::{BasicResponse as Basic, RequestProxy, Response, WindowIdentifier};
+//! use zvariant::Fd;
+//! use std::fs::File;
+//! use std::os::unix::io::AsRawFd;
+//!
+//! fn main() -> Result<()> {
+//!     let connection = Connection::new_session()?;
+//!     let proxy = PrintProxy::new(&connection)?;
+//!
+//!     let file = File::open("/home/bilelmoussaoui/gitlog.pdf").expect("file to print was not found");
+//!
+//!     let request_handle = proxy
+//!         .print(
+//!             WindowIdentifier::default(),
+//!             "test",
+//!             Fd::from(file.as_raw_fd()),
+//!             PrintOptions::default(),
+//!         )
+//!         .unwrap();
+//!
+//!     let request = RequestProxy::new(&connection, &request_handle)?;
+//!     request.on_response(|r: Response<Basic>| {
+//!         println!("{:#?}", r.is_ok());
+//!     })?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+mod paper;
+pub use self::paper::Paper;
+
+use crate::{BasicResponse, Error, HandleToken, RequestProxy, WindowIdentifier};
+use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::{collections::HashMap, fmt, str::FromStr};
+use strum_macros::{AsRefStr, Display, EnumString, IntoStaticStr, ToString};
+use zbus::{dbus_proxy, fdo::Result};
+use zvariant::{Fd, OwnedObjectPath, OwnedValue, Signature, Value};
+use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
+
+#[derive(
+    Debug, Clone, Deserialize, EnumString, AsRefStr, IntoStaticStr, ToString, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "lowercase")]
+/// The page orientation.
+pub enum Orientation {
+    /// Landscape.
+    Landscape,
+    /// Portrait.
+    Portrait,
+    #[strum(serialize = "reverse_landscape")]
+    /// Reverse landscape.
+    ReverseLandscape,
+    #[strum(serialize = "reverse_portrait")]
+    /// Reverse portrait.
+    ReversePortrait,
+}
+
+impl zvariant::Type for Orientation {
+    fn signature() -> Signature<'static> {
+        String::signature()
+    }
+}
+
+impl Serialize for Orientation {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        String::serialize(&self.to_string(), serializer)
+    }
+}
+
+#[derive(
+    Debug, Clone, Deserialize, EnumString, AsRefStr, IntoStaticStr, ToString, PartialEq, Eq,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+/// The print quality.
+pub enum Quality {
+    /// Draft quality.
+    Draft,
+    /// Low quality.
+    Low,
+    /// Normal quality.
+    Normal,
+    /// High quality.
+    High,
+}
+
+impl zvariant::Type for Quality {
+    fn signature() -> Signature<'static> {
+        String::signature()
+    }
+}
+
+impl Serialize for Quality {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        String::serialize(&self.to_string(), serializer)
+    }
+}
+
+#[derive(Debug, Clone, Copy, EnumString, AsRefStr, IntoStaticStr, Display, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+/// The duplex printing mode.
+pub enum Duplex {
+    /// Print on one side of the sheet.
+    Simplex,
+    /// Print on both sides, flipping the sheet along its short edge.
+    Horizontal,
+    /// Print on both sides, flipping the sheet along its long edge.
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy, EnumString, AsRefStr, IntoStaticStr, Display, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+/// The set of pages to print.
+pub enum PageSet {
+    /// All the pages.
+    All,
+    /// The even pages only.
+    Even,
+    /// The odd pages only.
+    Odd,
+}
+
+#[derive(Debug, Clone, Copy, EnumString, AsRefStr, IntoStaticStr, Display, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+/// Which pages to print.
+pub enum PrintPages {
+    /// All the pages.
+    All,
+    /// The selected part of the document.
+    Selection,
+    /// The current page.
+    Current,
+    /// The pages in [`Settings::page_ranges`].
+    ///
+    /// [`Settings::page_ranges`]: ./struct.Settings.html#structfield.page_ranges
+    Ranges,
+}
+
+#[derive(Debug, Clone, Copy, EnumString, AsRefStr, IntoStaticStr, Display, PartialEq, Eq)]
+/// The order of the pages on a sheet when printing several pages per sheet.
+pub enum NumberUpLayout {
+    #[strum(serialize = "lrtb")]
+    /// Left to right, top to bottom.
+    LeftToRightTopToBottom,
+    #[strum(serialize = "lrbt")]
+    /// Left to right, bottom to top.
+    LeftToRightBottomToTop,
+    #[strum(serialize = "rltb")]
+    /// Right to left, top to bottom.
+    RightToLeftTopToBottom,
+    #[strum(serialize = "rlbt")]
+    /// Right to left, bottom to top.
+    RightToLeftBottomToTop,
+    #[strum(serialize = "tblr")]
+    /// Top to bottom, left to right.
+    TopToBottomLeftToRight,
+    #[strum(serialize = "tbrl")]
+    /// Top to bottom, right to left.
+    TopToBottomRightToLeft,
+    #[strum(serialize = "btlr")]
+    /// Bottom to top, left to right.
+    BottomToTopLeftToRight,
+    #[strum(serialize = "btrl")]
+    /// Bottom to top, right to left.
+    BottomToTopRightToLeft,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An inclusive range of pages, numbered from zero.
+pub struct PageRange {
+    /// The first page of the range.
+    pub start: u32,
+    /// The last page of the range.
+    pub end: u32,
+}
+
+impl PageRange {
+    /// Creates a new range from `start` to `end`, both included.
+    pub fn new(start: u32, end: u32) -> Self {
+        Self { start, end }
+    }
+
+    /// Parses a list of page ranges, formatted like this: 0-2,4,9-11.
+    ///
+    /// ```
+    /// use ashpd::desktop::print::PageRange;
+    ///
+    /// let ranges = PageRange::parse_list("0-2,4,9-11").unwrap();
+    /// assert_eq!(ranges, vec![PageRange::new(0, 2), PageRange::new(4, 4), PageRange::new(9, 11)]);
+    /// assert_eq!(PageRange::format_list(&ranges), "0-2,4,9-11");
+    /// ```
+    pub fn parse_list(ranges: &str) -> std::result::Result<Vec<Self>, InvalidPageRange> {
+        if ranges.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        ranges.split(',').map(str::parse).collect()
+    }
+
+    /// Formats a list of page ranges, the opposite of [`PageRange::parse_list`].
+    ///
+    /// [`PageRange::parse_list`]: #method.parse_list
+    pub fn format_list(ranges: &[Self]) -> String {
+        ranges
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+impl FromStr for PageRange {
+    type Err = InvalidPageRange;
+
+    fn from_str(range: &str) -> std::result::Result<Self, Self::Err> {
+        let parse = |page: &str| {
+            page.trim()
+                .parse::<u32>()
+                .map_err(|_| InvalidPageRange(range.to_string()))
+        };
+        match range.split_once('-') {
+            Some((start, end)) => Ok(Self::new(parse(start)?, parse(end)?)),
+            None => {
+                let page = parse(range)?;
+                Ok(Self::new(page, page))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+/// A page range that doesn't follow the `start-end` or `page` format.
+pub struct InvalidPageRange(String);
+
+impl fmt::Display for InvalidPageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid page range: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidPageRange {}
+
+#[derive(Debug)]
+/// A key file that can't be read, or lacks the expected group.
+pub struct InvalidKeyFile(String);
+
+impl fmt::Display for InvalidKeyFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid key file: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidKeyFile {}
+
+#[derive(TypeDict, Debug, Default, Clone, PartialEq)]
+/// Print settings to set in the print dialog.
+///
+/// The portal exchanges every setting as a string, the typed fields are
+/// converted from and to that representation. Settings without a typed field,
+/// or holding a value that couldn't be parsed, are kept in [`Settings::other`]
+/// so that sending back the settings returned by the portal doesn't lose any of them.
+///
+/// [`Settings::other`]: #structfield.other
+pub struct Settings {
+    /// The page orientation.
+    pub orientation: Option<Orientation>,
+    /// A paper name according to [PWG 5101.1-2002](ftp://ftp.pwg.org/pub/pwg/candidates/cs-pwgmsn10-20020226-5101.1.pdf)
+    pub paper_format: Option<String>,
+    /// Paper width, in millimeters.
+    pub paper_width: Option<f64>,
+    /// Paper height, in millimeters.
+    pub paper_height: Option<f64>,
+    /// The number of copies to print.
+    pub n_copies: Option<u32>,
+    /// The default paper source.
+    pub default_source: Option<String>,
+    /// Print quality.
+    pub quality: Option<Quality>,
+    /// The resolution, sets both resolution-x & resolution-y
+    pub resolution: Option<u32>,
+    /// Whether to use color.
+    pub use_color: Option<bool>,
+    /// Duplex printing mode.
+    pub duplex: Option<Duplex>,
+    /// Whether to collate copies.
+    pub collate: Option<bool>,
+    /// Whether to reverse the order of printed pages.
+    pub reverse: Option<bool>,
+    /// A media type according to [PWG 5101.1-2002](ftp://ftp.pwg.org/pub/pwg/candidates/cs-pwgmsn10-20020226-5101.1.pdf)
+    pub media_type: Option<String>,
+    /// The dithering to use, one of fine, none, coarse, lineart, grayscale or error-diffusion.
+    pub dither: Option<String>,
+    /// The scale in percent
+    pub scale: Option<f64>,
+    /// What pages to print.
+    pub print_pages: Option<PrintPages>,
+    /// The page ranges to print when `print_pages` is [`PrintPages::Ranges`].
+    ///
+    /// [`PrintPages::Ranges`]: ./enum.PrintPages.html#variant.Ranges
+    pub page_ranges: Option<Vec<PageRange>>,
+    /// What pages to print, all, the even or the odd ones.
+    pub page_set: Option<PageSet>,
+    /// The finishings.
+    pub finishings: Option<String>,
+    /// The number of pages per sheet.
+    pub number_up: Option<u32>,
+    /// The order of the pages on a sheet.
+    pub number_up_layout: Option<NumberUpLayout>,
+    /// The output bin.
+    pub output_bin: Option<String>,
+    /// The horizontal resolution in dpi.
+    pub resolution_x: Option<u32>,
+    /// The vertical resolution in dpi.
+    pub resolution_y: Option<u32>,
+    /// The resolution in lpi (lines per inch).
+    pub print_lpi: Option<f64>,
+    /// Basename to use for print-to-file.
+    pub output_basename: Option<String>,
+    /// Format to use for print-to-file, one of PDF, PS, SVG
+    pub output_file_format: Option<String>,
+    /// The uri used for print-to file.
+    pub output_uri: Option<String>,
+    /// The remaining settings, by their key in the portal's dictionary.
+    pub other: HashMap<String, String>,
+}
+
+impl Settings {
+    /// Sets the orientation.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = Some(orientation);
+        self
+    }
+
+    /// Sets the paper name.
+    pub fn paper_format(mut self, paper_format: &str) -> Self {
+        self.paper_format = Some(paper_format.to_string());
+        self
+    }
+
+    /// Sets the paper width in millimeters.
+    pub fn paper_width(mut self, paper_width: f64) -> Self {
+        self.paper_width = Some(paper_width);
+        self
+    }
+
+    /// Sets the paper height in millimeters.
+    pub fn paper_height(mut self, paper_height: f64) -> Self {
+        self.paper_height = Some(paper_height);
+        self
+    }
+
+    /// Sets the number of copies to print.
+    pub fn n_copies(mut self, n_copies: u32) -> Self {
+        self.n_copies = Some(n_copies);
+        self
+    }
+
+    /// Sets the default paper source.
+    pub fn default_source(mut self, default_source: &str) -> Self {
+        self.default_source = Some(default_source.to_string());
+        self
+    }
+
+    /// Sets the print quality.
+    pub fn quality(mut self, quality: Quality) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// Sets the resolution, both resolution-x & resolution-y.
+    pub fn resolution(mut self, resolution: u32) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+
+    /// Sets whether to use color.
+    pub fn use_color(mut self, use_color: bool) -> Self {
+        self.use_color = Some(use_color);
+        self
+    }
+
+    /// Sets the duplex printing mode.
+    pub fn duplex(mut self, duplex: Duplex) -> Self {
+        self.duplex = Some(duplex);
+        self
+    }
+
+    /// Whether to collate copies.
+    pub fn collate(mut self, collate: bool) -> Self {
+        self.collate = Some(collate);
+        self
+    }
+
+    /// Sets whether to reverse the order of the printed pages.
+    pub fn reverse(mut self, reverse: bool) -> Self {
+        self.reverse = Some(reverse);
+        self
+    }
+
+    /// Sets the media type.
+    pub fn media_type(mut self, media_type: &str) -> Self {
+        self.media_type = Some(media_type.to_string());
+        self
+    }
+
+    /// Sets the dithering to use.
+    pub fn dither(mut self, dither: &str) -> Self {
+        self.dither = Some(dither.to_string());
+        self
+    }
+
+    /// Sets the page scale in percent.
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Sets what pages to print.
+    pub fn print_pages(mut self, print_pages: PrintPages) -> Self {
+        self.print_pages = Some(print_pages);
+        self
+    }
+
+    /// Sets the page ranges to print.
+    pub fn page_ranges(mut self, page_ranges: &[PageRange]) -> Self {
+        self.page_ranges = Some(page_ranges.to_vec());
+        self
+    }
+
+    /// Sets what pages to print, all, the even or the odd ones.
+    pub fn page_set(mut self, page_set: PageSet) -> Self {
+        self.page_set = Some(page_set);
+        self
+    }
+
+    /// Sets the finishings.
+    pub fn finishings(mut self, finishings: &str) -> Self {
+        self.finishings = Some(finishings.to_string());
+        self
+    }
+
+    /// Sets the number of pages per sheet.
+    pub fn number_up(mut self, number_up: u32) -> Self {
+        self.number_up = Some(number_up);
+        self
+    }
+
+    /// Sets the order of the pages on a sheet.
+    pub fn number_up_layout(mut self, number_up_layout: NumberUpLayout) -> Self {
+        self.number_up_layout = Some(number_up_layout);
+        self
+    }
+
+    /// Sets the output bin
+    pub fn output_bin(mut self, output_bin: &str) -> Self {
+        self.output_bin = Some(output_bin.to_string());
+        self
+    }
+
+    /// Sets the horizontal resolution in dpi.
+    pub fn resolution_x(mut self, resolution_x: u32) -> Self {
+        self.resolution_x = Some(resolution_x);
+        self
+    }
+
+    /// Sets the vertical resolution in dpi.
+    pub fn resolution_y(mut self, resolution_y: u32) -> Self {
+        self.resolution_y = Some(resolution_y);
+        self
+    }
+
+    /// Sets the resolution in lines per inch.
+    pub fn print_lpi(mut self, print_lpi: f64) -> Self {
+        self.print_lpi = Some(print_lpi);
+        self
+    }
+
+    /// Sets the print-to-file base name.
+    pub fn output_basename(mut self, output_basename: &str) -> Self {
+        self.output_basename = Some(output_basename.to_string());
+        self
+    }
+
+    /// Sets the print-to-file format, one of PS, PDF, SVG.
+    pub fn output_file_format(mut self, output_file_format: &str) -> Self {
+        self.output_file_format = Some(output_file_format.to_string());
+        self
+    }
+
+    /// Sets the print-to-file output uri.
+    pub fn output_uri(mut self, output_uri: &str) -> Self {
+        self.output_uri = Some(output_uri.to_string());
+        self
+    }
+
+    /// Sets a setting that has no typed field, by its key.
+    pub fn other(mut self, key: &str, value: &str) -> Self {
+        self.other.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Reads the settings from the `[Print Settings]` group of a key file,
+    /// as written by `gtk_print_settings_to_key_file`.
+    ///
+    /// ```
+    /// use ashpd::desktop::print::{Duplex, Settings};
+    ///
+    /// let settings = Settings::default().n_copies(2).duplex(Duplex::Vertical);
+    /// let key_file = settings.to_key_file();
+    /// assert_eq!(Settings::from_key_file(&key_file).unwrap(), settings);
+    /// ```
+    pub fn from_key_file(key_file: &str) -> std::result::Result<Self, InvalidKeyFile> {
+        Ok(Self::from_entries(read_key_file_group(
+            key_file,
+            SETTINGS_GROUP,
+        )?))
+    }
+
+    /// Writes the settings to a key file with a `[Print Settings]` group,
+    /// in the format used by `gtk_print_settings_to_key_file`.
+    pub fn to_key_file(&self) -> String {
+        let mut entries = self.to_entries();
+        // Keep the output stable, the order of the remaining settings is arbitrary.
+        entries.sort();
+        write_key_file_group(SETTINGS_GROUP, &entries)
+    }
+
+    /// Builds the settings from their string representation, keyed like the portal's dictionary.
+    pub(crate) fn from_entries<I: IntoIterator<Item = (String, String)>>(entries: I) -> Self {
+        let mut settings = Self::default();
+        for (key, value) in entries {
+            if !settings.set(&key, &value) {
+                settings.other.insert(key, value);
+            }
+        }
+        settings
+    }
+
+    /// The string representation of every setting, keyed like the portal's dictionary.
+    pub(crate) fn to_entries(&self) -> Vec<(String, String)> {
+        fn push<T: ToString>(entries: &mut Vec<(String, String)>, key: &str, value: &Option<T>) {
+            if let Some(value) = value {
+                entries.push((key.to_string(), value.to_string()));
+            }
+        }
+
+        let mut entries = vec![];
+        push(&mut entries, "orientation", &self.orientation);
+        push(&mut entries, "paper-format", &self.paper_format);
+        push(&mut entries, "paper-width", &self.paper_width);
+        push(&mut entries, "paper-height", &self.paper_height);
+        push(&mut entries, "n-copies", &self.n_copies);
+        push(&mut entries, "default-source", &self.default_source);
+        push(&mut entries, "quality", &self.quality);
+        push(&mut entries, "resolution", &self.resolution);
+        push(&mut entries, "use-color", &self.use_color);
+        push(&mut entries, "duplex", &self.duplex);
+        push(&mut entries, "collate", &self.collate);
+        push(&mut entries, "reverse", &self.reverse);
+        push(&mut entries, "media-type", &self.media_type);
+        push(&mut entries, "dither", &self.dither);
+        push(&mut entries, "scale", &self.scale);
+        push(&mut entries, "print-pages", &self.print_pages);
+        push(
+            &mut entries,
+            "page-ranges",
+            &self.page_ranges.as_deref().map(PageRange::format_list),
+        );
+        push(&mut entries, "page-set", &self.page_set);
+        push(&mut entries, "finishings", &self.finishings);
+        push(&mut entries, "number-up", &self.number_up);
+        push(&mut entries, "number-up-layout", &self.number_up_layout);
+        push(&mut entries, "output-bin", &self.output_bin);
+        push(&mut entries, "resolution-x", &self.resolution_x);
+        push(&mut entries, "resolution-y", &self.resolution_y);
+        push(&mut entries, "printer-lpi", &self.print_lpi);
+        push(&mut entries, "output-basename", &self.output_basename);
+        push(&mut entries, "output-file-format", &self.output_file_format);
+        push(&mut entries, "output-uri", &self.output_uri);
+        for (key, value) in &self.other {
+            entries.push((key.clone(), value.clone()));
+        }
+        entries
+    }
+
+    /// Sets the typed field matching `key`, returns whether it has one and `value` is valid for it.
+    fn set(&mut self, key: &str, value: &str) -> bool {
+        fn parse<T: FromStr>(field: &mut Option<T>, value: &str) -> bool {
+            match value.parse() {
+                Ok(value) => {
+                    *field = Some(value);
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+
+        match key {
+            "orientation" => parse(&mut self.orientation, value),
+            "paper-format" => parse(&mut self.paper_format, value),
+            "paper-width" => parse(&mut self.paper_width, value),
+            "paper-height" => parse(&mut self.paper_height, value),
+            "n-copies" => parse(&mut self.n_copies, value),
+            "default-source" => parse(&mut self.default_source, value),
+            "quality" => parse(&mut self.quality, value),
+            "resolution" => parse(&mut self.resolution, value),
+            "use-color" => parse(&mut self.use_color, value),
+            "duplex" => parse(&mut self.duplex, value),
+            "collate" => parse(&mut self.collate, value),
+            "reverse" => parse(&mut self.reverse, value),
+            "media-type" => parse(&mut self.media_type, value),
+            "dither" => parse(&mut self.dither, value),
+            "scale" => parse(&mut self.scale, value),
+            "print-pages" => parse(&mut self.print_pages, value),
+            "page-ranges" => match PageRange::parse_list(value) {
+                Ok(page_ranges) => {
+                    self.page_ranges = Some(page_ranges);
+                    true
+                }
+                Err(_) => false,
+            },
+            "page-set" => parse(&mut self.page_set, value),
+            "finishings" => parse(&mut self.finishings, value),
+            "number-up" => parse(&mut self.number_up, value),
+            "number-up-layout" => parse(&mut self.number_up_layout, value),
+            "output-bin" => parse(&mut self.output_bin, value),
+            "resolution-x" => parse(&mut self.resolution_x, value),
+            "resolution-y" => parse(&mut self.resolution_y, value),
+            "printer-lpi" => parse(&mut self.print_lpi, value),
+            "output-basename" => parse(&mut self.output_basename, value),
+            "output-file-format" => parse(&mut self.output_file_format, value),
+            "output-uri" => parse(&mut self.output_uri, value),
+            _ => false,
+        }
+    }
+}
+
+impl Serialize for Settings {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let entries = self.to_entries();
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, value) in &entries {
+            map.serialize_entry(key, &zvariant::SerializeValue(value))?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Settings {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let map = HashMap::<String, OwnedValue>::deserialize(deserializer)?;
+        Ok(Self::from_entries(map.into_iter().filter_map(
+            |(key, value)| {
+                // The portal only sends strings, be lenient with other basic types.
+                let value = match &*value {
+                    Value::Str(value) => value.as_str().to_string(),
+                    Value::Bool(value) => value.to_string(),
+                    Value::U32(value) => value.to_string(),
+                    Value::I32(value) => value.to_string(),
+                    Value::F64(value) => value.to_string(),
+                    _ => return None,
+                };
+                Some((key, value))
+            },
+        )))
+    }
+}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default, Clone, PartialEq)]
+/// Setup the printed pages.
+pub struct PageSetup {
+    /// the PPD name. It's the name to select a given driver.
+    #[zvariant(rename = "PPDName")]
+    pub ppdname: Option<String>,
+    /// The name of the page setup.
+    #[zvariant(rename = "Name")]
+    pub name: Option<String>,
+    /// The user-visible name of the page setup.
+    #[zvariant(rename = "DisplayName")]
+    pub display_name: Option<String>,
+    /// Paper width in millimeters.
+    #[zvariant(rename = "Width")]
+    pub width: Option<f64>,
+    /// Paper height in millimeters.
+    #[zvariant(rename = "Height")]
+    pub height: Option<f64>,
+    /// Top margin in millimeters.
+    #[zvariant(rename = "MarginTop")]
+    pub margin_top: Option<f64>,
+    /// Bottom margin in millimeters.
+    #[zvariant(rename = "MarginBottom")]
+    pub margin_bottom: Option<f64>,
+    /// Right margin in millimeters.
+    #[zvariant(rename = "MarginRight")]
+    pub margin_right: Option<f64>,
+    /// Left margin in millimeters.
+    #[zvariant(rename = "MarginLeft")]
+    pub margin_left: Option<f64>,
+    /// The page orientation.
+    #[zvariant(rename = "Orientation")]
+    pub orientation: Option<Orientation>,
+}
+
+impl PageSetup {
+    /// Creates a page setup for a standard paper size, with its default margins.
+    ///
+    /// The width and height are those of the paper in portrait orientation,
+    /// the orientation is applied when printing.
+    ///
+    /// ```
+    /// use ashpd::desktop::print::{Orientation, PageSetup, Paper};
+    ///
+    /// let page_setup = PageSetup::from_paper(Paper::IsoA4, Orientation::Landscape);
+    /// assert_eq!(page_setup.name.as_deref(), Some("iso_a4"));
+    /// assert_eq!(page_setup.width, Some(210.0));
+    /// assert_eq!(page_setup.paper(), Some(Paper::IsoA4));
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `paper` - The paper size
+    /// * `orientation` - The page orientation
+    pub fn from_paper(paper: Paper, orientation: Orientation) -> Self {
+        Self {
+            ppdname: paper.ppd_name().map(ToString::to_string),
+            name: Some(paper.name().to_string()),
+            display_name: Some(paper.display_name().to_string()),
+            width: Some(paper.width()),
+            height: Some(paper.height()),
+            margin_top: Some(paper.default_margin_top()),
+            margin_bottom: Some(paper.default_margin_bottom()),
+            margin_right: Some(paper.default_margin_right()),
+            margin_left: Some(paper.default_margin_left()),
+            orientation: Some(orientation),
+        }
+    }
+
+    /// The standard paper size of the page setup, looked up by its name or its dimensions.
+    pub fn paper(&self) -> Option<Paper> {
+        let by_name = |name: &Option<String>| name.as_deref().and_then(Paper::from_name);
+        let by_size = || match (self.width, self.height) {
+            (Some(width), Some(height)) => Paper::from_size(width, height),
+            _ => None,
+        };
+        by_name(&self.name)
+            .or_else(|| by_name(&self.ppdname))
+            .or_else(by_size)
+    }
+
+    /// Reads the page setup from the `[Page Setup]` group of a key file,
+    /// as written by `gtk_page_setup_to_key_file`.
+    ///
+    /// ```
+    /// use ashpd::desktop::print::{Orientation, PageSetup, Paper};
+    ///
+    /// let key_file = "\
+    /// [Page Setup]
+    /// Name=iso_a4
+    /// DisplayName=A4
+    /// Width=210
+    /// Height=297
+    /// MarginTop=6.3499999999999996
+    /// MarginBottom=14.224
+    /// MarginLeft=6.3499999999999996
+    /// MarginRight=6.3499999999999996
+    /// Orientation=reverse-landscape
+    /// ";
+    /// let page_setup = PageSetup::from_key_file(key_file).unwrap();
+    /// assert_eq!(page_setup.name.as_deref(), Some("iso_a4"));
+    /// assert_eq!(page_setup.width, Some(210.0));
+    /// assert_eq!(page_setup.margin_bottom, Some(14.224));
+    /// assert_eq!(page_setup.orientation, Some(Orientation::ReverseLandscape));
+    /// assert_eq!(page_setup.paper(), Some(Paper::IsoA4));
+    /// assert_eq!(PageSetup::from_key_file(&page_setup.to_key_file()).unwrap(), page_setup);
+    /// ```
+    pub fn from_key_file(key_file: &str) -> std::result::Result<Self, InvalidKeyFile> {
+        fn parse<T: FromStr>(key: &str, value: &str) -> std::result::Result<T, InvalidKeyFile> {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| InvalidKeyFile(format!("invalid value for {}: {}", key, value)))
+        }
+
+        let mut page_setup = Self::default();
+        for (key, value) in read_key_file_group(key_file, PAGE_SETUP_GROUP)? {
+            match key.as_str() {
+                "PPDName" => page_setup.ppdname = Some(value),
+                "Name" => page_setup.name = Some(value),
+                "DisplayName" => page_setup.display_name = Some(value),
+                "Width" => page_setup.width = Some(parse(&key, &value)?),
+                "Height" => page_setup.height = Some(parse(&key, &value)?),
+                "MarginTop" => page_setup.margin_top = Some(parse(&key, &value)?),
+                "MarginBottom" => page_setup.margin_bottom = Some(parse(&key, &value)?),
+                "MarginLeft" => page_setup.margin_left = Some(parse(&key, &value)?),
+                "MarginRight" => page_setup.margin_right = Some(parse(&key, &value)?),
+                // GTK writes the enum nicks, which use dashes.
+                "Orientation" => {
+                    page_setup.orientation = Some(parse(&key, &value.replace('-', "_"))?)
+                }
+                _ => (),
+            }
+        }
+        Ok(page_setup)
+    }
+
+    /// Writes the page setup to a key file with a `[Page Setup]` group,
+    /// in the format used by `gtk_page_setup_to_key_file`.
+    pub fn to_key_file(&self) -> String {
+        fn push<T: ToString>(entries: &mut Vec<(String, String)>, key: &str, value: &Option<T>) {
+            if let Some(value) = value {
+                entries.push((key.to_string(), value.to_string()));
+            }
+        }
+
+        let mut entries = vec![];
+        push(&mut entries, "PPDName", &self.ppdname);
+        push(&mut entries, "Name", &self.name);
+        push(&mut entries, "DisplayName", &self.display_name);
+        push(&mut entries, "Width", &self.width);
+        push(&mut entries, "Height", &self.height);
+        push(&mut entries, "MarginTop", &self.margin_top);
+        push(&mut entries, "MarginBottom", &self.margin_bottom);
+        push(&mut entries, "MarginLeft", &self.margin_left);
+        push(&mut entries, "MarginRight", &self.margin_right);
+        push(
+            &mut entries,
+            "Orientation",
+            &self
+                .orientation
+                .as_ref()
+                .map(|orientation| orientation.as_ref().replace('_', "-")),
+        );
+        write_key_file_group(PAGE_SETUP_GROUP, &entries)
+    }
+
+    /// Sets the ppdname.
+    pub fn ppdname(mut self, ppdname: &str) -> Self {
+        self.ppdname = Some(ppdname.to_string());
+        self
+    }
+
+    /// Sets the name of the page setup.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the user visible name of the page setup.
+    pub fn display_name(mut self, display_name: &str) -> Self {
+        self.display_name = Some(display_name.to_string());
+        self
+    }
+
+    /// Sets the orientation.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = Some(orientation);
+        self
+    }
+
+    /// Sets the page width.
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets the page height.
+    pub fn height(mut self, height: f64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Sets the page top margin.
+    pub fn margin_top(mut self, margin_top: f64) -> Self {
+        self.margin_top = Some(margin_top);
+        self
+    }
+
+    /// Sets the page bottom margin.
+    pub fn margin_bottom(mut self, margin_bottom: f64) -> Self {
+        self.margin_bottom = Some(margin_bottom);
+        self
+    }
+
+    /// Sets the page right margin.
+    pub fn margin_right(mut self, margin_right: f64) -> Self {
+        self.margin_right = Some(margin_right);
+        self
+    }
+
+    /// Sets the page margin left.
+    pub fn margin_left(mut self, margin_left: f64) -> Self {
+        self.margin_left = Some(margin_left);
+        self
+    }
+}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options on a prepare print request.
+pub struct PreparePrintOptions {
+    /// A string that will be used as the last element of the handle.
+    pub handle_token: Option<HandleToken>,
+    /// Whether to make the dialog modal.
+    pub modal: Option<bool>,
+}
+
+impl PreparePrintOptions {
+    /// Sets the handle token.
+    pub fn handle_token(mut self, handle_token: HandleToken) -> Self {
+        self.handle_token = Some(handle_token);
+        self
+    }
+
+    /// Sets whether the dialog should be a modal.
+    pub fn modal(mut self, modal: bool) -> Self {
+        self.modal = Some(modal);
+        self
+    }
+}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options on a print request.
+pub struct PrintOptions {
+    /// A string that will be used as the last element of the handle.
+    pub handle_token: Option<HandleToken>,
+    /// Whether to make the dialog modal.
+    pub modal: Option<bool>,
+    /// Token that was returned by a previous `prepare_print` call.
+    pub token: Option<u32>,
+}
+
+impl PrintOptions {
+    /// A token retrieved from a prepare print response.
+    pub fn token(mut self, token: u32) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Sets whether the dialog should be a modal.
+    pub fn modal(mut self, modal: bool) -> Self {
+        self.modal = Some(modal);
+        self
+    }
+
+    /// Sets the handle token.
+    pub fn handle_token(mut self, handle_token: HandleToken) -> Self {
+        self.handle_token = Some(handle_token);
+        self
+    }
+}
+
+#[derive(DeserializeDict, SerializeDict, TypeDict, Debug)]
+/// A response returned by a prepare print request.
+pub struct PreparePrint {
+    /// The printing settings.
+    pub settings: Settings,
+    #[zvariant(rename = "page-setup")]
+    /// The printed pages setup.
+    pub page_setup: PageSetup,
+    /// A token to pass to the print request.
+    pub token: u32,
+}
+
+impl PreparePrint {
+    /// Writes the settings and the page setup to a key file, to be restored
+    /// with [`Settings::from_key_file`] and [`PageSetup::from_key_file`]
+    /// and passed to the next print dialog.
+    ///
+    /// [`Settings::from_key_file`]: ./struct.Settings.html#method.from_key_file
+    /// [`PageSetup::from_key_file`]: ./struct.PageSetup.html#method.from_key_file
+    pub fn to_key_file(&self) -> String {
+        format!(
+            "{}\n{}",
+            self.settings.to_key_file(),
+            self.page_setup.to_key_file()
+        )
+    }
+}
+
+#[dbus_proxy(
+    interface = "org.freedesktop.portal.Print",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop"
+)]
+/// The interface lets sandboxed applications print.
+trait Print {
+    /// Presents a print dialog to the user and returns print settings and page setup.
+    ///
+    /// Returns a [`RequestProxy`] object path.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_window` - Identifier for the application window
+    /// * `title` - Title for the print dialog
+    /// * `settings` - [`Settings`]
+    /// * `page_setup` - [`PageSetup`]
+    /// * `options` - [`PreparePrintOptions`]
+    ///
+    /// [`Settings`]: ./struct.Settings.html
+    /// [`PageSetup`]: ./struct.PageSetup.html
+    /// [`PreparePrintOptions`]: ./struct.PreparePrintOptions.html
+    /// [`RequestProxy`]: ../request/struct.RequestProxy.html
+    fn prepare_print(
+        &self,
+        parent_window: WindowIdentifier,
+        title: &str,
+        settings: Settings,
+        page_setup: PageSetup,
+        options: PreparePrintOptions,
+    ) -> Result<OwnedObjectPath>;
+
+    /// Asks to print a file.
+    /// The file must be passed in the form of a file descriptor open for reading.
+    /// This ensures that sandboxed applications only print files that they have access to.
+    ///
+    /// Returns a [`RequestProxy`] object path.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_window` - The application window identifier
+    /// * `title` - The title for the print dialog
+    /// * `fd` - File descriptor for reading the content to print
+    /// * `options` - [`PrintOptions`]
+    ///
+    /// [`PrintOptions`]: ./struct.PrintOptions.html
+    /// [`RequestProxy`]: ../request/struct.RequestProxy.html
+    fn print(
+        &self,
+        parent_window: WindowIdentifier,
+        title: &str,
+        fd: Fd,
+        options: PrintOptions,
+    ) -> Result<OwnedObjectPath>;
+
+    /// version property
+    #[dbus_proxy(property, name = "version")]
+    fn version(&self) -> Result<u32>;
+}
+
+const SETTINGS_GROUP: &str = "Print Settings";
+const PAGE_SETUP_GROUP: &str = "Page Setup";
+
+/// Reads the entries of a group of a `GKeyFile` formatted file.
+fn read_key_file_group(
+    key_file: &str,
+    group: &str,
+) -> std::result::Result<Vec<(String, String)>, InvalidKeyFile> {
+    let mut entries = None;
+    let mut in_group = false;
+    for line in key_file.lines() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            let name = line
+                .trim_end()
+                .strip_suffix(']')
+                .ok_or_else(|| InvalidKeyFile(format!("invalid group: {}", line)))?;
+            in_group = &name[1..] == group;
+            if in_group {
+                entries.get_or_insert_with(Vec::new);
+            }
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| InvalidKeyFile(format!("invalid line: {}", line)))?;
+        if let (true, Some(entries)) = (in_group, entries.as_mut()) {
+            entries.push((key.trim_end().to_string(), unescape_key_file_value(value)));
+        }
+    }
+    entries.ok_or_else(|| InvalidKeyFile(format!("no [{}] group", group)))
+}
+
+fn write_key_file_group(group: &str, entries: &[(String, String)]) -> String {
+    let mut key_file = format!("[{}]\n", group);
+    for (key, value) in entries {
+        key_file.push_str(&format!("{}={}\n", key, escape_key_file_value(value)));
+    }
+    key_file
+}
+
+fn escape_key_file_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            // Only the leading whitespace would get trimmed.
+            ' ' if i == 0 => escaped.push_str("\\s"),
+            '\t' if i == 0 => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\\' => escaped.push_str("\\\\"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_key_file_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.trim_start().chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[derive(Debug)]
+/// A print job, showing the print dialog then printing a file with the chosen settings.
+///
+/// ```no_run
+/// use ashpd::desktop::print::{PrintJob, Settings};
+/// use ashpd::WindowIdentifier;
+/// use std::path::Path;
+///
+/// fn main() -> ashpd::Result<()> {
+///     let response = PrintJob::new("Report")
+///         .file(Path::new("/home/bilelmoussaoui/report.pdf"))
+///         .settings(Settings::default().n_copies(2))
+///         .run(WindowIdentifier::default())?;
+///
+///     println!("{:#?}", response.settings);
+///     Ok(())
+/// }
+/// ```
+pub struct PrintJob {
+    title: String,
+    file: Option<PathBuf>,
+    settings: Settings,
+    page_setup: PageSetup,
+    modal: Option<bool>,
+}
+
+impl PrintJob {
+    /// Creates a new print job.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title of the print dialog
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            file: None,
+            settings: Settings::default(),
+            page_setup: PageSetup::default(),
+            modal: None,
+        }
+    }
+
+    /// Sets the file to print, e.g. a PDF document.
+    pub fn file(mut self, file: &Path) -> Self {
+        self.file = Some(file.to_path_buf());
+        self
+    }
+
+    /// Sets the settings the print dialog starts with.
+    pub fn settings(mut self, settings: Settings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Sets the page setup the print dialog starts with.
+    pub fn page_setup(mut self, page_setup: PageSetup) -> Self {
+        self.page_setup = page_setup;
+        self
+    }
+
+    /// Sets whether the dialog should be a modal.
+    pub fn modal(mut self, modal: bool) -> Self {
+        self.modal = Some(modal);
+        self
+    }
+
+    /// Shows the print dialog and prints the file once the user accepts it.
+    ///
+    /// Returns the settings and page setup chosen by the user,
+    /// they can be used to start the next print job with.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_window` - Identifier for the application window
+    pub fn run(self, parent_window: WindowIdentifier) -> crate::Result<PreparePrint> {
+        let path = self
+            .file
+            .ok_or_else(|| Error::InvalidArgument("no file to print".to_string()))?;
+        // Open the file before showing the dialog, so a missing file doesn't get the user through it.
+        let file = File::open(&path)?;
+
+        let connection = zbus::Connection::new_session()?;
+        let proxy = PrintProxy::new(&connection)?;
+
+        let options = PreparePrintOptions {
+            modal: self.modal,
+            ..Default::default()
+        };
+        let handle = proxy.prepare_print(
+            parent_window.clone(),
+            &self.title,
+            self.settings,
+            self.page_setup,
+            options,
+        )?;
+        let request = RequestProxy::new(&connection, &handle)?;
+        let response = request.receive_response::<PreparePrint>()??;
+
+        let options = PrintOptions {
+            modal: self.modal,
+            token: Some(response.token),
+            ..Default::default()
+        };
+        let handle = proxy.print(
+            parent_window,
+            &self.title,
+            Fd::from(file.as_raw_fd()),
+            options,
+        )?;
+        let request = RequestProxy::new(&connection, &handle)?;
+        request.receive_response::<BasicResponse>()??;
+        Ok(response)
+    }
+}
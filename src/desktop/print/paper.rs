@@ -0,0 +1,612 @@
+/// One inch in millimeters.
+const INCH: f64 = 25.4;
+/// The largest difference, in millimeters, between two sizes considered the same.
+const TOLERANCE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A media size from the [PWG 5101.1](ftp://ftp.pwg.org/pub/pwg/candidates/cs-pwgmsn10-20020226-5101.1.pdf) standard.
+///
+/// ```
+/// use ashpd::desktop::print::Paper;
+///
+/// assert_eq!(Paper::IsoA4.name(), "iso_a4");
+/// assert_eq!(Paper::IsoA4.pwg_name(), "iso_a4_210x297mm");
+/// assert_eq!(Paper::from_size(297.0, 210.0), Some(Paper::IsoA4));
+/// assert_eq!(Paper::from_name("na_letter"), Some(Paper::NaLetter));
+/// ```
+pub enum Paper {
+    /// A0, 841 x 1189 mm.
+    IsoA0,
+    /// A1, 594 x 841 mm.
+    IsoA1,
+    /// A2, 420 x 594 mm.
+    IsoA2,
+    /// A3, 297 x 420 mm.
+    IsoA3,
+    /// A4, 210 x 297 mm.
+    IsoA4,
+    /// A5, 148 x 210 mm.
+    IsoA5,
+    /// A6, 105 x 148 mm.
+    IsoA6,
+    /// A7, 74 x 105 mm.
+    IsoA7,
+    /// A8, 52 x 74 mm.
+    IsoA8,
+    /// A9, 37 x 52 mm.
+    IsoA9,
+    /// A10, 26 x 37 mm.
+    IsoA10,
+    /// B0, 1000 x 1414 mm.
+    IsoB0,
+    /// B1, 707 x 1000 mm.
+    IsoB1,
+    /// B2, 500 x 707 mm.
+    IsoB2,
+    /// B3, 353 x 500 mm.
+    IsoB3,
+    /// B4, 250 x 353 mm.
+    IsoB4,
+    /// B5, 176 x 250 mm.
+    IsoB5,
+    /// B6, 125 x 176 mm.
+    IsoB6,
+    /// B7, 88 x 125 mm.
+    IsoB7,
+    /// B8, 62 x 88 mm.
+    IsoB8,
+    /// B9, 44 x 62 mm.
+    IsoB9,
+    /// B10, 31 x 44 mm.
+    IsoB10,
+    /// C0, 917 x 1297 mm.
+    IsoC0,
+    /// C1, 648 x 917 mm.
+    IsoC1,
+    /// C2, 458 x 648 mm.
+    IsoC2,
+    /// C3, 324 x 458 mm.
+    IsoC3,
+    /// C4 envelope, 229 x 324 mm.
+    IsoC4,
+    /// C5 envelope, 162 x 229 mm.
+    IsoC5,
+    /// C6 envelope, 114 x 162 mm.
+    IsoC6,
+    /// C7, 81 x 114 mm.
+    IsoC7,
+    /// C8, 57 x 81 mm.
+    IsoC8,
+    /// C9, 40 x 57 mm.
+    IsoC9,
+    /// C10, 28 x 40 mm.
+    IsoC10,
+    /// DL envelope, 110 x 220 mm.
+    IsoDl,
+    /// US Letter, 8.5 x 11 in.
+    NaLetter,
+    /// US Legal, 8.5 x 14 in.
+    NaLegal,
+    /// Executive, 7.25 x 10.5 in.
+    NaExecutive,
+    /// Ledger or Tabloid, 11 x 17 in.
+    NaLedger,
+    /// Government Letter, 8 x 10 in.
+    NaGovtLetter,
+    /// Invoice or Statement, 5.5 x 8.5 in.
+    NaInvoice,
+    /// Index card, 3 x 5 in.
+    NaIndex3x5,
+    /// Index card, 4 x 6 in.
+    NaIndex4x6,
+    /// Index card, 5 x 8 in.
+    NaIndex5x8,
+    /// #9 envelope, 3.875 x 8.875 in.
+    NaNumber9,
+    /// #10 envelope, 4.125 x 9.5 in.
+    NaNumber10,
+    /// Monarch envelope, 3.875 x 7.5 in.
+    NaMonarch,
+    /// Personal envelope, 3.625 x 6.5 in.
+    NaPersonal,
+    /// A2 envelope, 4.375 x 5.75 in.
+    NaA2,
+}
+
+struct PaperInfo {
+    paper: Paper,
+    pwg_name: &'static str,
+    ppd_name: Option<&'static str>,
+    display_name: &'static str,
+    /// The width in millimeters.
+    width: f64,
+    /// The height in millimeters.
+    height: f64,
+}
+
+const fn paper(
+    paper: Paper,
+    pwg_name: &'static str,
+    ppd_name: Option<&'static str>,
+    display_name: &'static str,
+    width: f64,
+    height: f64,
+) -> PaperInfo {
+    PaperInfo {
+        paper,
+        pwg_name,
+        ppd_name,
+        display_name,
+        width,
+        height,
+    }
+}
+
+const PAPERS: &[PaperInfo] = &[
+    paper(
+        Paper::IsoA0,
+        "iso_a0_841x1189mm",
+        Some("A0"),
+        "A0",
+        841.0,
+        1189.0,
+    ),
+    paper(
+        Paper::IsoA1,
+        "iso_a1_594x841mm",
+        Some("A1"),
+        "A1",
+        594.0,
+        841.0,
+    ),
+    paper(
+        Paper::IsoA2,
+        "iso_a2_420x594mm",
+        Some("A2"),
+        "A2",
+        420.0,
+        594.0,
+    ),
+    paper(
+        Paper::IsoA3,
+        "iso_a3_297x420mm",
+        Some("A3"),
+        "A3",
+        297.0,
+        420.0,
+    ),
+    paper(
+        Paper::IsoA4,
+        "iso_a4_210x297mm",
+        Some("A4"),
+        "A4",
+        210.0,
+        297.0,
+    ),
+    paper(
+        Paper::IsoA5,
+        "iso_a5_148x210mm",
+        Some("A5"),
+        "A5",
+        148.0,
+        210.0,
+    ),
+    paper(
+        Paper::IsoA6,
+        "iso_a6_105x148mm",
+        Some("A6"),
+        "A6",
+        105.0,
+        148.0,
+    ),
+    paper(
+        Paper::IsoA7,
+        "iso_a7_74x105mm",
+        Some("A7"),
+        "A7",
+        74.0,
+        105.0,
+    ),
+    paper(Paper::IsoA8, "iso_a8_52x74mm", Some("A8"), "A8", 52.0, 74.0),
+    paper(Paper::IsoA9, "iso_a9_37x52mm", Some("A9"), "A9", 37.0, 52.0),
+    paper(
+        Paper::IsoA10,
+        "iso_a10_26x37mm",
+        Some("A10"),
+        "A10",
+        26.0,
+        37.0,
+    ),
+    paper(
+        Paper::IsoB0,
+        "iso_b0_1000x1414mm",
+        Some("ISOB0"),
+        "B0",
+        1000.0,
+        1414.0,
+    ),
+    paper(
+        Paper::IsoB1,
+        "iso_b1_707x1000mm",
+        Some("ISOB1"),
+        "B1",
+        707.0,
+        1000.0,
+    ),
+    paper(
+        Paper::IsoB2,
+        "iso_b2_500x707mm",
+        Some("ISOB2"),
+        "B2",
+        500.0,
+        707.0,
+    ),
+    paper(
+        Paper::IsoB3,
+        "iso_b3_353x500mm",
+        Some("ISOB3"),
+        "B3",
+        353.0,
+        500.0,
+    ),
+    paper(
+        Paper::IsoB4,
+        "iso_b4_250x353mm",
+        Some("ISOB4"),
+        "B4",
+        250.0,
+        353.0,
+    ),
+    paper(
+        Paper::IsoB5,
+        "iso_b5_176x250mm",
+        Some("ISOB5"),
+        "B5",
+        176.0,
+        250.0,
+    ),
+    paper(
+        Paper::IsoB6,
+        "iso_b6_125x176mm",
+        Some("ISOB6"),
+        "B6",
+        125.0,
+        176.0,
+    ),
+    paper(
+        Paper::IsoB7,
+        "iso_b7_88x125mm",
+        Some("ISOB7"),
+        "B7",
+        88.0,
+        125.0,
+    ),
+    paper(
+        Paper::IsoB8,
+        "iso_b8_62x88mm",
+        Some("ISOB8"),
+        "B8",
+        62.0,
+        88.0,
+    ),
+    paper(
+        Paper::IsoB9,
+        "iso_b9_44x62mm",
+        Some("ISOB9"),
+        "B9",
+        44.0,
+        62.0,
+    ),
+    paper(
+        Paper::IsoB10,
+        "iso_b10_31x44mm",
+        Some("ISOB10"),
+        "B10",
+        31.0,
+        44.0,
+    ),
+    paper(
+        Paper::IsoC0,
+        "iso_c0_917x1297mm",
+        Some("EnvC0"),
+        "C0",
+        917.0,
+        1297.0,
+    ),
+    paper(
+        Paper::IsoC1,
+        "iso_c1_648x917mm",
+        Some("EnvC1"),
+        "C1",
+        648.0,
+        917.0,
+    ),
+    paper(
+        Paper::IsoC2,
+        "iso_c2_458x648mm",
+        Some("EnvC2"),
+        "C2",
+        458.0,
+        648.0,
+    ),
+    paper(
+        Paper::IsoC3,
+        "iso_c3_324x458mm",
+        Some("EnvC3"),
+        "C3",
+        324.0,
+        458.0,
+    ),
+    paper(
+        Paper::IsoC4,
+        "iso_c4_229x324mm",
+        Some("EnvC4"),
+        "C4",
+        229.0,
+        324.0,
+    ),
+    paper(
+        Paper::IsoC5,
+        "iso_c5_162x229mm",
+        Some("EnvC5"),
+        "C5",
+        162.0,
+        229.0,
+    ),
+    paper(
+        Paper::IsoC6,
+        "iso_c6_114x162mm",
+        Some("EnvC6"),
+        "C6",
+        114.0,
+        162.0,
+    ),
+    paper(
+        Paper::IsoC7,
+        "iso_c7_81x114mm",
+        Some("EnvC7"),
+        "C7",
+        81.0,
+        114.0,
+    ),
+    paper(
+        Paper::IsoC8,
+        "iso_c8_57x81mm",
+        Some("EnvC8"),
+        "C8",
+        57.0,
+        81.0,
+    ),
+    paper(
+        Paper::IsoC9,
+        "iso_c9_40x57mm",
+        Some("EnvC9"),
+        "C9",
+        40.0,
+        57.0,
+    ),
+    paper(
+        Paper::IsoC10,
+        "iso_c10_28x40mm",
+        Some("EnvC10"),
+        "C10",
+        28.0,
+        40.0,
+    ),
+    paper(
+        Paper::IsoDl,
+        "iso_dl_110x220mm",
+        Some("EnvDL"),
+        "DL Envelope",
+        110.0,
+        220.0,
+    ),
+    paper(
+        Paper::NaLetter,
+        "na_letter_8.5x11in",
+        Some("Letter"),
+        "US Letter",
+        8.5 * INCH,
+        11.0 * INCH,
+    ),
+    paper(
+        Paper::NaLegal,
+        "na_legal_8.5x14in",
+        Some("Legal"),
+        "US Legal",
+        8.5 * INCH,
+        14.0 * INCH,
+    ),
+    paper(
+        Paper::NaExecutive,
+        "na_executive_7.25x10.5in",
+        Some("Executive"),
+        "Executive",
+        7.25 * INCH,
+        10.5 * INCH,
+    ),
+    paper(
+        Paper::NaLedger,
+        "na_ledger_11x17in",
+        Some("Tabloid"),
+        "Tabloid",
+        11.0 * INCH,
+        17.0 * INCH,
+    ),
+    paper(
+        Paper::NaGovtLetter,
+        "na_govt-letter_8x10in",
+        None,
+        "Government Letter",
+        8.0 * INCH,
+        10.0 * INCH,
+    ),
+    paper(
+        Paper::NaInvoice,
+        "na_invoice_5.5x8.5in",
+        Some("Statement"),
+        "Statement",
+        5.5 * INCH,
+        8.5 * INCH,
+    ),
+    paper(
+        Paper::NaIndex3x5,
+        "na_index-3x5_3x5in",
+        None,
+        "Index Card 3x5",
+        3.0 * INCH,
+        5.0 * INCH,
+    ),
+    paper(
+        Paper::NaIndex4x6,
+        "na_index-4x6_4x6in",
+        None,
+        "Index Card 4x6",
+        4.0 * INCH,
+        6.0 * INCH,
+    ),
+    paper(
+        Paper::NaIndex5x8,
+        "na_index-5x8_5x8in",
+        None,
+        "Index Card 5x8",
+        5.0 * INCH,
+        8.0 * INCH,
+    ),
+    paper(
+        Paper::NaNumber9,
+        "na_number-9_3.875x8.875in",
+        Some("Env9"),
+        "#9 Envelope",
+        3.875 * INCH,
+        8.875 * INCH,
+    ),
+    paper(
+        Paper::NaNumber10,
+        "na_number-10_4.125x9.5in",
+        Some("Env10"),
+        "#10 Envelope",
+        4.125 * INCH,
+        9.5 * INCH,
+    ),
+    paper(
+        Paper::NaMonarch,
+        "na_monarch_3.875x7.5in",
+        Some("EnvMonarch"),
+        "Monarch Envelope",
+        3.875 * INCH,
+        7.5 * INCH,
+    ),
+    paper(
+        Paper::NaPersonal,
+        "na_personal_3.625x6.5in",
+        Some("EnvPersonal"),
+        "Personal Envelope",
+        3.625 * INCH,
+        6.5 * INCH,
+    ),
+    paper(
+        Paper::NaA2,
+        "na_a2_4.375x5.75in",
+        None,
+        "A2 Envelope",
+        4.375 * INCH,
+        5.75 * INCH,
+    ),
+];
+
+impl Paper {
+    fn info(self) -> &'static PaperInfo {
+        PAPERS
+            .iter()
+            .find(|info| info.paper == self)
+            .expect("every paper is in the table")
+    }
+
+    /// The PWG name without the dimensions, e.g. `iso_a4`, as used by GTK.
+    pub fn name(self) -> &'static str {
+        let pwg_name = self.info().pwg_name;
+        &pwg_name[..pwg_name.rfind('_').unwrap()]
+    }
+
+    /// The self describing PWG name, e.g. `iso_a4_210x297mm`.
+    pub fn pwg_name(self) -> &'static str {
+        self.info().pwg_name
+    }
+
+    /// The name used in PPD files, if the size has a common one.
+    pub fn ppd_name(self) -> Option<&'static str> {
+        self.info().ppd_name
+    }
+
+    /// A user visible name, in English.
+    pub fn display_name(self) -> &'static str {
+        self.info().display_name
+    }
+
+    /// The width in millimeters, in portrait orientation.
+    pub fn width(self) -> f64 {
+        self.info().width
+    }
+
+    /// The height in millimeters, in portrait orientation.
+    pub fn height(self) -> f64 {
+        self.info().height
+    }
+
+    /// The default top margin in millimeters.
+    pub fn default_margin_top(self) -> f64 {
+        0.25 * INCH
+    }
+
+    /// The default bottom margin in millimeters.
+    ///
+    /// Like GTK, it leaves more room on the sizes commonly used by inkjet printers.
+    pub fn default_margin_bottom(self) -> f64 {
+        match self {
+            Self::NaLetter | Self::NaLegal | Self::IsoA4 => 0.56 * INCH,
+            _ => 0.25 * INCH,
+        }
+    }
+
+    /// The default left margin in millimeters.
+    pub fn default_margin_left(self) -> f64 {
+        0.25 * INCH
+    }
+
+    /// The default right margin in millimeters.
+    pub fn default_margin_right(self) -> f64 {
+        0.25 * INCH
+    }
+
+    /// Looks up a size by its PWG name, with or without the dimensions, or by its PPD name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the size, e.g. `iso_a4`, `iso_a4_210x297mm` or `A4`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        PAPERS
+            .iter()
+            .find(|info| {
+                info.pwg_name == name || info.paper.name() == name || info.ppd_name == Some(name)
+            })
+            .map(|info| info.paper)
+    }
+
+    /// Looks up a size by its dimensions, in either orientation.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width in millimeters.
+    /// * `height` - The height in millimeters.
+    pub fn from_size(width: f64, height: f64) -> Option<Self> {
+        let matches = |a: f64, b: f64| (a - b).abs() <= TOLERANCE;
+        PAPERS
+            .iter()
+            .find(|info| {
+                (matches(info.width, width) && matches(info.height, height))
+                    || (matches(info.width, height) && matches(info.height, width))
+            })
+            .map(|info| info.paper)
+    }
+}
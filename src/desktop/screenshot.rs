@@ -49,7 +49,11 @@
 //!     Ok(())
 //! }
 //! ```
-use crate::{HandleToken, WindowIdentifier};
+use crate::{Error, HandleToken, RequestProxy, WindowIdentifier};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::{fmt, fs};
+use url::Url;
 use zbus::{dbus_proxy, fdo::Result};
 use zvariant::OwnedObjectPath;
 use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
@@ -92,6 +96,55 @@ pub struct Screenshot {
     pub uri: String,
 }
 
+impl Screenshot {
+    /// The local path of the screenshot.
+    ///
+    /// Inside a sandbox, it's usually a path exported by the document portal.
+    pub fn path(&self) -> crate::Result<PathBuf> {
+        Url::parse(&self.uri)
+            .ok()
+            .filter(|url| url.scheme() == "file")
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| Error::InvalidArgument(format!("not a local file: {}", self.uri)))
+    }
+
+    /// Reads the content of the screenshot's file, usually a PNG image.
+    ///
+    /// # Arguments
+    ///
+    /// * `delete` - Whether to delete the file once read. Failing to delete it is an error,
+    ///   inside a sandbox the document portal may only expose it for reading.
+    ///   To keep the content even then, read it without deleting and call [`Screenshot::delete`].
+    ///
+    /// [`Screenshot::delete`]: #method.delete
+    pub fn read(&self, delete: bool) -> crate::Result<Vec<u8>> {
+        let bytes = fs::read(self.path()?)?;
+        if delete {
+            self.delete()?;
+        }
+        Ok(bytes)
+    }
+
+    /// Deletes the screenshot's file.
+    pub fn delete(&self) -> crate::Result<()> {
+        fs::remove_file(self.path()?)?;
+        Ok(())
+    }
+
+    /// Reads and decodes the screenshot's image.
+    ///
+    /// # Arguments
+    ///
+    /// * `delete` - Whether to delete the file once read, see [`Screenshot::read`].
+    ///
+    /// [`Screenshot::read`]: #method.read
+    #[cfg(feature = "image")]
+    pub fn decode(&self, delete: bool) -> crate::Result<image::DynamicImage> {
+        let bytes = self.read(delete)?;
+        Ok(image::load_from_memory(&bytes)?)
+    }
+}
+
 #[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
 /// Specified options on a pick color request.
 pub struct PickColorOptions {
@@ -107,13 +160,34 @@ impl PickColorOptions {
     }
 }
 
-#[derive(SerializeDict, DeserializeDict, TypeDict, Debug)]
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Clone, Copy, PartialEq)]
 /// A response to a pick color request.
+///
+/// The color is in the sRGB color space, each component going from 0 to 1.
+/// See [`color_hex`] to store it with serde.
+///
+/// [`color_hex`]: ./color_hex/index.html
+///
+/// ```
+/// use ashpd::desktop::screenshot::Color;
+///
+/// let color: Color = "#3584e4".parse().unwrap();
+/// assert_eq!(color.to_rgb8(), (0x35, 0x84, 0xe4));
+/// assert_eq!(color.to_css(), "rgb(53, 132, 228)");
+/// assert_eq!(color.to_string(), "#3584e4");
+/// ```
 pub struct Color {
     color: ([f64; 3]),
 }
 
 impl Color {
+    /// Creates a new color from its sRGB components, from 0 to 1.
+    pub fn new(red: f64, green: f64, blue: f64) -> Self {
+        Self {
+            color: [red, green, blue],
+        }
+    }
+
     /// Red.
     pub fn red(&self) -> f64 {
         self.color[0]
@@ -128,6 +202,251 @@ impl Color {
     pub fn blue(&self) -> f64 {
         self.color[2]
     }
+
+    /// Creates a color from its 8-bit sRGB components.
+    pub fn from_rgb8(red: u8, green: u8, blue: u8) -> Self {
+        Self::new(
+            red as f64 / 255.0,
+            green as f64 / 255.0,
+            blue as f64 / 255.0,
+        )
+    }
+
+    /// The 8-bit sRGB components, rounded to the nearest value.
+    pub fn to_rgb8(&self) -> (u8, u8, u8) {
+        let to_u8 = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        (to_u8(self.red()), to_u8(self.green()), to_u8(self.blue()))
+    }
+
+    /// Parses a `#rrggbb` or `#rgb` hex color.
+    pub fn from_hex(hex: &str) -> std::result::Result<Self, InvalidColor> {
+        let invalid = || InvalidColor(hex.to_string());
+        let digits = hex.trim().strip_prefix('#').ok_or_else(invalid)?;
+        // `from_str_radix` would accept a leading sign too.
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let component = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+        match digits.len() {
+            6 => Ok(Self::from_rgb8(
+                component(&digits[0..2])?,
+                component(&digits[2..4])?,
+                component(&digits[4..6])?,
+            )),
+            3 => Ok(Self::from_rgb8(
+                component(&digits[0..1])? * 17,
+                component(&digits[1..2])? * 17,
+                component(&digits[2..3])? * 17,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The color formatted as `#rrggbb`.
+    pub fn to_hex(&self) -> String {
+        let (red, green, blue) = self.to_rgb8();
+        format!("#{:02x}{:02x}{:02x}", red, green, blue)
+    }
+
+    /// Parses a CSS `rgb()` color, with either 8-bit or percentage components.
+    ///
+    /// The alpha component of `rgba()` colors is ignored.
+    pub fn from_css(css: &str) -> std::result::Result<Self, InvalidColor> {
+        let invalid = || InvalidColor(css.to_string());
+        let css = css.trim();
+        let arguments = css
+            .strip_prefix("rgba(")
+            .or_else(|| css.strip_prefix("rgb("))
+            .and_then(|css| css.strip_suffix(')'))
+            .ok_or_else(invalid)?;
+        // Both the legacy comma separated and the space separated syntaxes are valid.
+        let components = arguments
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|c| !c.is_empty())
+            .map(|c| match c.strip_suffix('%') {
+                Some(percent) => percent.parse::<f64>().map(|p| p / 100.0),
+                None => c.parse::<f64>().map(|c| c / 255.0),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        match components.as_slice() {
+            [red, green, blue] | [red, green, blue, _] => Ok(Self::new(
+                red.clamp(0.0, 1.0),
+                green.clamp(0.0, 1.0),
+                blue.clamp(0.0, 1.0),
+            )),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The color formatted as a CSS `rgb()` color.
+    pub fn to_css(&self) -> String {
+        let (red, green, blue) = self.to_rgb8();
+        format!("rgb({}, {}, {})", red, green, blue)
+    }
+
+    /// Creates a color from its hue in degrees, saturation and lightness from 0 to 1.
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        Self::from_hue_chroma(hue, chroma, lightness - chroma / 2.0)
+    }
+
+    /// The hue in degrees, saturation and lightness from 0 to 1.
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        let (max, min) = self.max_min();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        (self.hue(), saturation, lightness)
+    }
+
+    /// Creates a color from its hue in degrees, saturation and value from 0 to 1.
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Self {
+        let chroma = value * saturation;
+        Self::from_hue_chroma(hue, chroma, value - chroma)
+    }
+
+    /// The hue in degrees, saturation and value from 0 to 1.
+    pub fn to_hsv(&self) -> (f64, f64, f64) {
+        let (max, min) = self.max_min();
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (self.hue(), saturation, max)
+    }
+
+    /// Creates a color from linear RGB components, applying the sRGB transfer function.
+    pub fn from_linear(red: f64, green: f64, blue: f64) -> Self {
+        let encode = |c: f64| {
+            if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+        Self::new(encode(red), encode(green), encode(blue))
+    }
+
+    /// The linear RGB components, removing the sRGB transfer function.
+    pub fn to_linear(&self) -> (f64, f64, f64) {
+        let decode = |c: f64| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        (
+            decode(self.red()),
+            decode(self.green()),
+            decode(self.blue()),
+        )
+    }
+
+    fn max_min(&self) -> (f64, f64) {
+        let max = self.red().max(self.green()).max(self.blue());
+        let min = self.red().min(self.green()).min(self.blue());
+        (max, min)
+    }
+
+    fn hue(&self) -> f64 {
+        let (max, min) = self.max_min();
+        let chroma = max - min;
+        if chroma == 0.0 {
+            return 0.0;
+        }
+        let (red, green, blue) = (self.red(), self.green(), self.blue());
+        let hue = if max == red {
+            (green - blue) / chroma
+        } else if max == green {
+            (blue - red) / chroma + 2.0
+        } else {
+            (red - green) / chroma + 4.0
+        };
+        (hue * 60.0).rem_euclid(360.0)
+    }
+
+    fn from_hue_chroma(hue: f64, chroma: f64, m: f64) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (red, green, blue) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Self::new(red + m, green + m, blue + m)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl FromStr for Color {
+    type Err = InvalidColor;
+
+    /// Parses either a hex or a CSS `rgb()` color.
+    fn from_str(color: &str) -> std::result::Result<Self, Self::Err> {
+        if color.trim_start().starts_with('#') {
+            Self::from_hex(color)
+        } else {
+            Self::from_css(color)
+        }
+    }
+}
+
+#[derive(Debug)]
+/// A color that isn't a valid hex or CSS `rgb()` color.
+pub struct InvalidColor(String);
+
+impl fmt::Display for InvalidColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid color: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidColor {}
+
+/// Serializes a [`Color`] as a `#rrggbb` string, for storing it in the usual serde formats
+/// as its own serde implementation is the D-Bus dictionary of the pick color response.
+///
+/// ```
+/// use ashpd::desktop::screenshot::{color_hex, Color};
+/// use serde::de::value::{Error, StrDeserializer};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Palette {
+///     #[serde(with = "ashpd::desktop::screenshot::color_hex")]
+///     accent: Color,
+/// }
+///
+/// let color = color_hex::deserialize(StrDeserializer::<Error>::new("#3584e4")).unwrap();
+/// assert_eq!(color, Color::from_rgb8(0x35, 0x84, 0xe4));
+/// assert!(color_hex::deserialize(StrDeserializer::<Error>::new("#+f+f+f")).is_err());
+/// ```
+///
+/// [`Color`]: ./struct.Color.html
+pub mod color_hex {
+    use super::Color;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    /// Serializes the color as a `#rrggbb` string.
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color.to_hex())
+    }
+
+    /// Deserializes the color from a `#rrggbb` or `#rgb` string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Color::from_hex(&hex).map_err(de::Error::custom)
+    }
 }
 
 #[cfg(feature = "feature_gtk")]
@@ -187,3 +506,57 @@ trait Screenshot {
     #[dbus_proxy(property, name = "version")]
     fn version(&self) -> Result<u32>;
 }
+
+/// Takes a screenshot and waits for the user to accept it.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `interactive` - Whether the dialog should offer customization before taking the screenshot
+pub fn capture(parent_window: WindowIdentifier, interactive: bool) -> crate::Result<Screenshot> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = ScreenshotProxy::new(&connection)?;
+    let handle = proxy.screenshot(
+        parent_window,
+        ScreenshotOptions::default().interactive(interactive),
+    )?;
+    let request = RequestProxy::new(&connection, &handle)?;
+    Ok(request.receive_response::<Screenshot>()??)
+}
+
+/// Takes a screenshot and returns the content of its file, usually a PNG image.
+///
+/// The file is left in place, use [`capture`] and [`Screenshot::read`] to delete it.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `interactive` - Whether the dialog should offer customization before taking the screenshot
+///
+/// [`capture`]: ./fn.capture.html
+/// [`Screenshot::read`]: ./struct.Screenshot.html#method.read
+pub fn capture_to_bytes(
+    parent_window: WindowIdentifier,
+    interactive: bool,
+) -> crate::Result<Vec<u8>> {
+    capture(parent_window, interactive)?.read(false)
+}
+
+/// Takes a screenshot and decodes its image.
+///
+/// The file is left in place, use [`capture`] and [`Screenshot::decode`] to delete it.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `interactive` - Whether the dialog should offer customization before taking the screenshot
+///
+/// [`capture`]: ./fn.capture.html
+/// [`Screenshot::decode`]: ./struct.Screenshot.html#method.decode
+#[cfg(feature = "image")]
+pub fn capture_to_image(
+    parent_window: WindowIdentifier,
+    interactive: bool,
+) -> crate::Result<image::DynamicImage> {
+    capture(parent_window, interactive)?.decode(false)
+}
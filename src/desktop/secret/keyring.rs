@@ -0,0 +1,385 @@
+//! # Examples
+//!
+//! Store a password and look it up later
+//!
+//! ```no_run
+//! use ashpd::desktop::secret::keyring::Keyring;
+//! use std::collections::HashMap;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     let mut keyring = Keyring::open()?;
+//!
+//!     let mut attributes = HashMap::new();
+//!     attributes.insert("service", "imap.example.org");
+//!     attributes.insert("user", "bilelmoussaoui");
+//!     keyring.create_item("IMAP password", &attributes, b"hunter2", true)?;
+//!
+//!     let mut query = HashMap::new();
+//!     query.insert("service", "imap.example.org");
+//!     for item in keyring.search_items(&query) {
+//!         println!("{}: {} bytes", item.label(), item.secret().len());
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+use super::{retrieve_with, RetrieveOptions, SecretBytes};
+use crate::helper::data_home_dir;
+use byteorder::LE;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::PathBuf;
+use zeroize::{Zeroize, Zeroizing};
+use zvariant::EncodingContext;
+use zvariant_derive::Type;
+
+/// Identifies the keyring files, followed by the encoded `KeyringFile`.
+const MAGIC: &[u8] = b"ASHPD-KEYRING";
+const VERSION: u32 = 1;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+#[derive(Serialize, Deserialize, Type)]
+struct KeyringFile {
+    version: u32,
+    /// The token the secret was retrieved with, it's not confidential.
+    token: String,
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Type, Clone)]
+/// A secret stored in a [`Keyring`], along with its lookup attributes.
+///
+/// [`Keyring`]: ./struct.Keyring.html
+pub struct Item {
+    label: String,
+    attributes: HashMap<String, String>,
+    secret: Vec<u8>,
+}
+
+impl Item {
+    /// The user visible label of the item.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The attributes the item can be looked up with.
+    pub fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    /// The stored secret.
+    pub fn secret(&self) -> &[u8] {
+        &self.secret
+    }
+
+    fn matches(&self, attributes: &HashMap<&str, &str>) -> bool {
+        attributes
+            .iter()
+            .all(|(key, value)| self.attributes.get(*key).map(|v| v.as_str()) == Some(*value))
+    }
+}
+
+impl std::fmt::Debug for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Item")
+            .field("label", &self.label)
+            .field("attributes", &self.attributes)
+            .finish()
+    }
+}
+
+impl Drop for Item {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+/// A file backed store of secrets, encrypted with a key derived from the
+/// application's master secret retrieved through the Secret portal.
+///
+/// The keyring lives in `$XDG_DATA_HOME/keyrings`, which is private to the application
+/// when running inside a sandbox. Every change is written to the disk immediately.
+#[derive(Debug)]
+pub struct Keyring {
+    path: PathBuf,
+    options: RetrieveOptions,
+    secret: SecretBytes,
+    items: Vec<Item>,
+}
+
+impl Keyring {
+    /// Opens the default keyring of the application, creating it if needed.
+    pub fn open() -> crate::Result<Self> {
+        Self::open_named("default")
+    }
+
+    /// Opens a keyring by its name, creating it if needed.
+    ///
+    /// If the portal hands back a new token for the master secret, the keyring
+    /// is encrypted again to keep track of it.
+    /// If the secret itself changed, the keyring can't be decrypted anymore
+    /// and an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The keyring name, used as its file name, so it can't contain a `/`.
+    pub fn open_named(name: &str) -> crate::Result<Self> {
+        if name.is_empty() || name == "." || name == ".." || name.contains(&['/', '\0'][..]) {
+            return Err(crate::Error::InvalidArgument(format!(
+                "invalid keyring name {:?}",
+                name
+            )));
+        }
+        let path = data_home_dir()?
+            .join("keyrings")
+            .join(format!("{}.keyring", name));
+
+        let file = match fs::read(&path) {
+            Ok(content) => Some(decode_file(&content)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut options = RetrieveOptions {
+            token: file.as_ref().and_then(file_token),
+            ..Default::default()
+        };
+        let secret = retrieve_with(&mut options)?;
+
+        let mut keyring = Self {
+            path,
+            options,
+            secret,
+            items: vec![],
+        };
+        match file {
+            Some(file) => {
+                keyring.items = decrypt(&file, &keyring.secret)?;
+                if keyring.token() != file.token {
+                    keyring.save()?;
+                }
+            }
+            None => keyring.save()?,
+        }
+        Ok(keyring)
+    }
+
+    /// All the stored items.
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// The items having all the given attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `attributes` - The attributes to match.
+    pub fn search_items(&self, attributes: &HashMap<&str, &str>) -> Vec<&Item> {
+        self.items
+            .iter()
+            .filter(|item| item.matches(attributes))
+            .collect()
+    }
+
+    /// Stores a new secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - A user visible label of the item.
+    /// * `attributes` - The attributes to look up the item with.
+    /// * `secret` - The secret to store.
+    /// * `replace` - Whether to replace an item that has exactly the same attributes.
+    pub fn create_item(
+        &mut self,
+        label: &str,
+        attributes: &HashMap<&str, &str>,
+        secret: &[u8],
+        replace: bool,
+    ) -> crate::Result<()> {
+        let item = Item {
+            label: label.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            secret: secret.to_vec(),
+        };
+        let existing = self
+            .items
+            .iter()
+            .position(|i| i.attributes == item.attributes);
+        match existing {
+            Some(position) if replace => self.items[position] = item,
+            _ => self.items.push(item),
+        }
+        self.save()
+    }
+
+    /// Deletes the items having all the given attributes.
+    ///
+    /// Returns the number of deleted items.
+    ///
+    /// # Arguments
+    ///
+    /// * `attributes` - The attributes to match.
+    pub fn delete_items(&mut self, attributes: &HashMap<&str, &str>) -> crate::Result<usize> {
+        let count = self.items.len();
+        self.items.retain(|item| !item.matches(attributes));
+        let deleted = count - self.items.len();
+        if deleted > 0 {
+            self.save()?;
+        }
+        Ok(deleted)
+    }
+
+    /// Retrieves the master secret again with the current token,
+    /// and encrypts the keyring with a new salt and nonce.
+    ///
+    /// If the portal hands back a new token or secret, the keyring is encrypted with them,
+    /// the items are already decrypted so they are kept.
+    pub fn rotate(&mut self) -> crate::Result<()> {
+        let mut options = RetrieveOptions {
+            token: self.options.token.clone(),
+            ..Default::default()
+        };
+        let secret = retrieve_with(&mut options)?;
+        self.options = options;
+        self.secret = secret;
+        self.save()
+    }
+
+    fn token(&self) -> &str {
+        self.options.token.as_deref().unwrap_or_default()
+    }
+
+    fn save(&self) -> crate::Result<()> {
+        let mut salt = vec![0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = vec![0; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let plaintext = Zeroizing::new(
+            zvariant::to_bytes(encoding_context(), &self.items)
+                .map_err(|e| invalid_data(&e.to_string()))?,
+        );
+        let cipher = ChaCha20Poly1305::new_from_slice(&*derive_key(&self.secret, &salt))
+            .expect("32 bytes is a valid key length");
+        let aad = additional_data(self.token(), &salt);
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| invalid_data("failed to encrypt the keyring"))?;
+
+        let file = KeyringFile {
+            version: VERSION,
+            token: self.token().to_string(),
+            salt,
+            nonce,
+            ciphertext,
+        };
+        let mut content = MAGIC.to_vec();
+        content.extend(
+            zvariant::to_bytes(encoding_context(), &file)
+                .map_err(|e| invalid_data(&e.to_string()))?,
+        );
+
+        // Write to a temporary file first, so a crash never leaves a truncated keyring behind.
+        let dir = self.path.parent().unwrap();
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)?;
+        let tmp_path = self.path.with_extension("keyring.tmp");
+        let mut tmp = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp_path)?;
+        tmp.write_all(&content)?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+fn derive_key(secret: &SecretBytes, salt: &[u8]) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0; 32]);
+    Hkdf::<Sha256>::new(Some(salt), secret.as_bytes())
+        .expand(b"ashpd keyring", &mut *key)
+        .expect("32 bytes is a valid output length");
+    key
+}
+
+fn decrypt(file: &KeyringFile, secret: &SecretBytes) -> crate::Result<Vec<Item>> {
+    if file.nonce.len() != NONCE_LEN {
+        return Err(invalid_data("invalid keyring nonce").into());
+    }
+    let cipher = ChaCha20Poly1305::new_from_slice(&*derive_key(secret, &file.salt))
+        .expect("32 bytes is a valid key length");
+    let aad = additional_data(&file.token, &file.salt);
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&file.nonce),
+            Payload {
+                msg: &file.ciphertext,
+                aad: &aad,
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| {
+            invalid_data("failed to decrypt the keyring, the application secret has changed")
+        })?;
+    zvariant::from_slice(&plaintext, encoding_context())
+        .map_err(|e| invalid_data(&e.to_string()).into())
+}
+
+/// The token stored in a keyring file, if any.
+fn file_token(file: &KeyringFile) -> Option<String> {
+    Some(file.token.clone()).filter(|token| !token.is_empty())
+}
+
+fn decode_file(content: &[u8]) -> crate::Result<KeyringFile> {
+    if !content.starts_with(MAGIC) {
+        return Err(invalid_data("not a keyring file").into());
+    }
+    let file: KeyringFile = zvariant::from_slice(&content[MAGIC.len()..], encoding_context())
+        .map_err(|e| invalid_data(&e.to_string()))?;
+    if file.version != VERSION {
+        return Err(invalid_data("unsupported keyring version").into());
+    }
+    Ok(file)
+}
+
+/// Authenticates the unencrypted part of the keyring file along with the items.
+fn additional_data(token: &str, salt: &[u8]) -> Vec<u8> {
+    let mut aad = MAGIC.to_vec();
+    aad.extend_from_slice(&VERSION.to_le_bytes());
+    aad.extend_from_slice(token.as_bytes());
+    aad.extend_from_slice(salt);
+    aad
+}
+
+fn encoding_context() -> EncodingContext<LE> {
+    EncodingContext::<LE>::new_dbus(0)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,206 @@
+//! # Examples
+//!
+//! ```no_run
+//! use ashpd::desktop::secret::{SecretProxy, RetrieveOptions};
+//! use ashpd::{RequestProxy, Response, BasicResponse as Basic};
+//! use zbus::{fdo::Result, Connection};
+//! use zvariant::Fd;
+//! use std::fs::File;
+//! use std::os::unix::io::AsRawFd;
+//!
+//! fn main() -> Result<()> {
+//!     let connection = Connection::new_session()?;
+//!     let proxy = SecretProxy::new(&connection)?;
+//!
+//!     let file = File::open("test.txt").unwrap();
+//!
+//!     let handle = proxy.retrieve_secret(Fd::from(file.as_raw_fd()), RetrieveOptions::default())?;
+//!     let request = RequestProxy::new(&connection, &handle)?;
+//!     request.on_response(|r: Response<Basic>| {
+//!         println!("{:#?}", r);
+//!     })?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Retrieve the secret directly
+//!
+//! ```no_run
+//! use ashpd::desktop::secret::{self, RetrieveOptions};
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     let mut options = RetrieveOptions::default();
+//!     let secret = secret::retrieve_with(&mut options)?;
+//!     println!("{} bytes, token: {:?}", secret.as_bytes().len(), options.token);
+//!
+//!     Ok(())
+//! }
+//! ```
+use crate::helper::pipe;
+use crate::{HandleToken, RequestProxy};
+use std::fmt;
+use std::io::{self, Read};
+use std::os::unix::io::AsRawFd;
+use zbus::{dbus_proxy, fdo::Result};
+use zeroize::Zeroize;
+use zvariant::{Fd, OwnedObjectPath};
+use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options on a retrieve secret request.
+pub struct RetrieveOptions {
+    /// A string that will be used as the last element of the handle.
+    pub handle_token: Option<HandleToken>,
+    /// A string returned by a previous call to `retrieve_secret`
+    pub token: Option<String>,
+}
+
+impl RetrieveOptions {
+    /// Sets the handle token.
+    pub fn handle_token(mut self, handle_token: HandleToken) -> Self {
+        self.handle_token = Some(handle_token);
+        self
+    }
+
+    /// Sets the token received on a previous call to `retrieve_secret`.
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug)]
+/// A response to a retrieve secret request.
+pub struct RetrieveSecret {
+    /// A string to pass to the next `retrieve_secret` calls.
+    pub token: Option<String>,
+}
+
+/// A secret retrieved from the portal, wiped from memory once dropped.
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    /// The secret.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for SecretBytes {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes([REDACTED; {}])", self.0.len())
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[dbus_proxy(
+    interface = "org.freedesktop.portal.Secret",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop"
+)]
+/// The interface lets sandboxed applications retrieve a per-application secret.
+/// The secret can then be used for encrypting confidential data inside the sandbox.
+trait Secret {
+    /// Retrieves a master secret for a sandboxed application.
+    ///
+    /// Returns a [`RequestProxy`] object path..
+    ///
+    /// # Arguments
+    ///
+    /// * `fd` - Writable file descriptor for transporting the secret.
+    /// * `options` - A `RetrieveOptions`
+    ///
+    /// [`RetrieveOptions`]: ./struct.RetrieveOptions.html
+    /// [`RequestProxy`]: ../../request/struct.RequestProxy.html
+    fn retrieve_secret(&self, fd: Fd, options: RetrieveOptions) -> Result<OwnedObjectPath>;
+
+    /// version property
+    #[dbus_proxy(property, name = "version")]
+    fn version(&self) -> Result<u32>;
+}
+
+/// Retrieves the master secret of the application.
+///
+/// See [`retrieve_with`] to pass the token returned by a previous call.
+///
+/// [`retrieve_with`]: ./fn.retrieve_with.html
+pub fn retrieve() -> crate::Result<SecretBytes> {
+    retrieve_with(&mut RetrieveOptions::default())
+}
+
+/// Retrieves the master secret of the application.
+///
+/// The token returned by the portal, if any, is stored in `options`
+/// so they can be reused for the next calls.
+///
+/// # Arguments
+///
+/// * `options` - A [`RetrieveOptions`]
+///
+/// [`RetrieveOptions`]: ./struct.RetrieveOptions.html
+pub fn retrieve_with(options: &mut RetrieveOptions) -> crate::Result<SecretBytes> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = SecretProxy::new(&connection)?;
+
+    let (mut reader, writer) = pipe()?;
+    let handle = proxy.retrieve_secret(
+        Fd::from(writer.as_raw_fd()),
+        RetrieveOptions {
+            // A handle token can't be used for more than one request.
+            handle_token: options.handle_token.take(),
+            token: options.token.clone(),
+        },
+    )?;
+    // The portal has its own copy of the write end now, drop ours
+    // so reading stops once the portal is done writing.
+    drop(writer);
+
+    let request = RequestProxy::new(&connection, &handle)?;
+    let response = request.receive_response::<RetrieveSecret>()??;
+    if response.token.is_some() {
+        options.token = response.token;
+    }
+
+    Ok(read_secret(&mut reader)?)
+}
+
+/// Reads a secret, growing the buffer by hand as a reallocation by `read_to_end`
+/// would leave a copy of the secret behind in freed memory.
+fn read_secret(reader: &mut impl Read) -> io::Result<SecretBytes> {
+    // The master secret is 64 bytes long, which avoids growing the buffer at all.
+    let mut secret = SecretBytes(vec![0; 64]);
+    let mut len = 0;
+    loop {
+        if len == secret.0.len() {
+            let mut grown = SecretBytes(vec![0; 2 * len]);
+            grown.0[..len].copy_from_slice(&secret.0[..len]);
+            // Zeroizes the previous buffer.
+            secret = grown;
+        }
+        match reader.read(&mut secret.0[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    // Doesn't reallocate, the unused tail was never written to.
+    secret.0.truncate(len);
+    Ok(secret)
+}
+
+/// Store secrets in a local keyring encrypted with the application's master secret.
+#[cfg(feature = "keyring")]
+pub mod keyring;
@@ -24,7 +24,86 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Trash a list of files, falling back to the local trash if the portal is not available
+//!
+//! ```no_run
+//! use ashpd::desktop::trash;
+//! use std::path::PathBuf;
+//!
+//! fn main() {
+//!     let files = vec![
+//!         PathBuf::from("/home/bilelmoussaoui/Downloads/adwaita-day.jpg"),
+//!         PathBuf::from("/home/bilelmoussaoui/Downloads/adwaita-night.jpg"),
+//!     ];
+//!
+//!     for (path, result) in trash::trash_paths(&files) {
+//!         if let Err(err) = result {
+//!             println!("couldn't trash {}: {}", path.display(), err);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Without the portal, the files of the home filesystem are moved to `$XDG_DATA_HOME/Trash`
+//!
+//! ```
+//! use ashpd::desktop::trash;
+//! use std::ffi::OsStr;
+//! use std::fs;
+//! use std::os::unix::ffi::OsStrExt;
+//!
+//! // Without a session bus, there is no trash portal.
+//! std::env::set_var("DBUS_SESSION_BUS_ADDRESS", "unix:path=/nonexistent");
+//! let dir = std::env::temp_dir().join(format!("ashpd-trash-{}", std::process::id()));
+//! let data_home = dir.join("data");
+//! fs::create_dir_all(&data_home).unwrap();
+//! std::env::set_var("XDG_DATA_HOME", &data_home);
+//! let trash = data_home.join("Trash");
+//!
+//! // A file trashed twice gets a numbered name the second time.
+//! let path = dir.join("my report.txt");
+//! fs::write(&path, b"first").unwrap();
+//! trash::trash_path(&path).unwrap();
+//! fs::write(&path, b"second").unwrap();
+//! trash::trash_path(&path).unwrap();
+//! assert_eq!(fs::read(trash.join("files/my report.txt")).unwrap(), b"first");
+//! assert_eq!(fs::read(trash.join("files/my report.txt.2")).unwrap(), b"second");
+//!
+//! // The original path is percent-encoded byte by byte.
+//! let info = fs::read_to_string(trash.join("info/my report.txt.2.trashinfo")).unwrap();
+//! let dir = dir.canonicalize().unwrap();
+//! assert!(info.starts_with("[Trash Info]\n"));
+//! assert!(info.contains(&format!("\nPath={}/my%20report.txt\n", dir.display())));
+//!
+//! let path = dir.join(OsStr::from_bytes(b"caf\xe9"));
+//! fs::write(&path, b"").unwrap();
+//! trash::trash_path(&path).unwrap();
+//! let info = fs::read(trash.join("info").join(OsStr::from_bytes(b"caf\xe9.trashinfo"))).unwrap();
+//! let info = String::from_utf8(info).unwrap();
+//! assert!(info.contains(&format!("\nPath={}/caf%E9\n", dir.display())));
+//!
+//! // A symlink is trashed itself, not its target.
+//! let target = dir.join("target");
+//! fs::write(&target, b"").unwrap();
+//! std::os::unix::fs::symlink(&target, dir.join("link")).unwrap();
+//! trash::trash_path(&dir.join("link")).unwrap();
+//! assert!(target.exists());
+//! assert!(fs::symlink_metadata(trash.join("files/link")).unwrap().file_type().is_symlink());
+//!
+//! fs::remove_dir_all(&dir).unwrap();
+//! ```
+use crate::helper::{data_home_dir, PATH_ESCAPE_SET};
+use crate::Error;
+use percent_encoding::percent_encode;
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
 use zbus::{dbus_proxy, fdo::Result};
 use zvariant::Fd;
 use zvariant_derive::Type;
@@ -59,3 +138,210 @@ trait Trash {
     #[dbus_proxy(property, name = "version")]
     fn version(&self) -> Result<u32>;
 }
+
+/// Moves a file to the trash.
+///
+/// The file is handed to the trash portal if it's available. Otherwise, it's moved
+/// to the trash following the [FreeDesktop Trash specification](https://specifications.freedesktop.org/trash-spec/trashspec-latest.html).
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to trash
+pub fn trash_path(path: &Path) -> crate::Result<()> {
+    let connection = zbus::Connection::new_session().ok();
+    let proxy = connection.as_ref().and_then(|c| TrashProxy::new(c).ok());
+    trash(proxy.as_ref(), path)
+}
+
+/// Moves a list of files to the trash.
+///
+/// Returns the result of moving each file to the trash, see [`trash_path`].
+///
+/// # Arguments
+///
+/// * `paths` - The paths of the files to trash
+///
+/// [`trash_path`]: ./fn.trash_path.html
+pub fn trash_paths(paths: &[PathBuf]) -> Vec<(PathBuf, crate::Result<()>)> {
+    let connection = zbus::Connection::new_session().ok();
+    let proxy = connection.as_ref().and_then(|c| TrashProxy::new(c).ok());
+    paths
+        .iter()
+        .map(|path| (path.clone(), trash(proxy.as_ref(), path)))
+        .collect()
+}
+
+fn trash(proxy: Option<&TrashProxy>, path: &Path) -> crate::Result<()> {
+    if let Some(proxy) = proxy {
+        // Like the local trash, a symlink is trashed itself rather than its target.
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_PATH | libc::O_NOFOLLOW)
+            .open(path)?;
+        match proxy.trash_file(Fd::from(file.as_raw_fd())) {
+            Ok(TrashStatus::Succeeded) => return Ok(()),
+            Ok(TrashStatus::Failed) => {
+                return Err(Error::Portal(format!(
+                    "failed to move {} to the trash",
+                    path.display()
+                )))
+            }
+            Err(err) if !is_unavailable(&err) => return Err(err.into()),
+            Err(_) => (),
+        }
+    }
+    trash_locally(path)?;
+    Ok(())
+}
+
+/// Whether the error means that there's no trash portal to talk to.
+fn is_unavailable(err: &zbus::fdo::Error) -> bool {
+    matches!(
+        err,
+        zbus::fdo::Error::ServiceUnknown(_)
+            | zbus::fdo::Error::NameHasNoOwner(_)
+            | zbus::fdo::Error::UnknownInterface(_)
+            | zbus::fdo::Error::UnknownMethod(_)
+            | zbus::fdo::Error::UnknownObject(_)
+    )
+}
+
+/// Moves a file to the home trash, or to the trash of the mount point it lives in.
+fn trash_locally(path: &Path) -> io::Result<()> {
+    let path = absolute_path(path)?;
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "cannot trash a root directory")
+    })?;
+    let device = fs::symlink_metadata(&path)?.dev();
+
+    let home_trash = data_home_dir()?.join("Trash");
+    let (trash_dir, info_path) = if closest_existing_device(&home_trash)? == device {
+        (home_trash, path.clone())
+    } else {
+        let top_dir = top_dir(&path, device)?;
+        let trash_dir = top_dir_trash_dir(&top_dir)?;
+        let info_path = path.strip_prefix(&top_dir).unwrap().to_path_buf();
+        (trash_dir, info_path)
+    };
+
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&files_dir)?;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&info_dir)?;
+
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode(info_path.as_os_str().as_bytes(), PATH_ESCAPE_SET),
+        deletion_date()
+    );
+
+    // Reserve a name by creating its info file first, as mandated by the specification.
+    let mut n = 1;
+    let mut trash_name = file_name.to_os_string();
+    let (info_file, mut file) = loop {
+        let mut info_name = trash_name.clone();
+        info_name.push(".trashinfo");
+        let info_file = info_dir.join(info_name);
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_file)
+        {
+            Ok(file) => break (info_file, file),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                n += 1;
+                trash_name = file_name.to_os_string();
+                trash_name.push(format!(".{}", n));
+            }
+            Err(err) => return Err(err),
+        }
+    };
+
+    let result = file
+        .write_all(info.as_bytes())
+        .and_then(|_| fs::rename(&path, files_dir.join(&trash_name)));
+    if result.is_err() {
+        let _ = fs::remove_file(&info_file);
+    }
+    result
+}
+
+fn absolute_path(path: &Path) -> io::Result<PathBuf> {
+    // Only the parent is canonicalized, a symlink is trashed itself rather than its target.
+    let path = std::env::current_dir()?.join(path);
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(file_name)) => Ok(parent.canonicalize()?.join(file_name)),
+        _ => Ok(path),
+    }
+}
+
+/// The device of `path`, or of its closest existing parent if it doesn't exist yet.
+fn closest_existing_device(path: &Path) -> io::Result<u64> {
+    let mut path = path;
+    loop {
+        match fs::metadata(path) {
+            Ok(metadata) => return Ok(metadata.dev()),
+            Err(err) => match path.parent() {
+                Some(parent) => path = parent,
+                None => return Err(err),
+            },
+        }
+    }
+}
+
+/// The mount point of the filesystem `path` lives in.
+fn top_dir(path: &Path, device: u64) -> io::Result<PathBuf> {
+    let mut top_dir = path;
+    while let Some(parent) = top_dir.parent() {
+        if fs::metadata(parent)?.dev() != device {
+            break;
+        }
+        top_dir = parent;
+    }
+    Ok(top_dir.to_path_buf())
+}
+
+/// `$topdir/.Trash/$uid` if the administrator created a shared trash, `$topdir/.Trash-$uid` otherwise.
+fn top_dir_trash_dir(top_dir: &Path) -> io::Result<PathBuf> {
+    let uid = unsafe { libc::getuid() };
+    let shared_trash = top_dir.join(".Trash");
+    if let Ok(metadata) = fs::symlink_metadata(&shared_trash) {
+        let is_sticky = metadata.mode() & libc::S_ISVTX != 0;
+        if metadata.is_dir() && is_sticky {
+            let trash_dir = shared_trash.join(uid.to_string());
+            if fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(&trash_dir)
+                .is_ok()
+            {
+                return Ok(trash_dir);
+            }
+        }
+    }
+    let mut trash_name = OsString::from(".Trash-");
+    trash_name.push(uid.to_string());
+    Ok(top_dir.join(trash_name))
+}
+
+/// The current local time, in the `YYYY-MM-DDThh:mm:ss` format.
+fn deletion_date() -> String {
+    let now = unsafe { libc::time(std::ptr::null_mut()) };
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    unsafe { libc::localtime_r(&now, &mut tm) };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
@@ -57,15 +57,40 @@
 //!     Ok(())
 //! }
 //! ```
-use crate::WindowIdentifier;
+//!
+//! Sets a wallpaper from a file, after checking it's an image:
+//!
+//! ```no_run
+//! use ashpd::desktop::wallpaper::{self, SetOn};
+//! use ashpd::WindowIdentifier;
+//! use std::path::Path;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     wallpaper::set_from_path(
+//!         WindowIdentifier::default(),
+//!         Path::new("/home/bilelmoussaoui/adwaita-day.jpg"),
+//!         SetOn::Both,
+//!         true,
+//!     )?;
+//!     Ok(())
+//! }
+//! ```
+use crate::{BasicResponse, Error, RequestProxy, WindowIdentifier};
 use serde::{self, Deserialize, Serialize, Serializer};
+use std::ffi::CString;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::Path;
 use strum_macros::{AsRefStr, EnumString, IntoStaticStr, ToString};
+use url::Url;
 use zbus::{dbus_proxy, fdo::Result};
 use zvariant::{Fd, OwnedObjectPath, Signature};
 use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
 
 #[derive(Deserialize, Debug, Clone, Copy, AsRefStr, EnumString, IntoStaticStr, ToString)]
-#[serde(rename = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 /// Where to set the wallpaper on.
 pub enum SetOn {
     /// Set the wallpaper only on the lockscreen.
@@ -167,3 +192,173 @@ trait Wallpaper {
     #[dbus_proxy(property, name = "version")]
     fn version(&self) -> Result<u32>;
 }
+
+/// Sets the wallpaper from a local image file.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `path` - The path of the image
+/// * `set_on` - Where to set the wallpaper on
+/// * `show_preview` - Whether to show a preview of the wallpaper, the portal may show one anyway
+pub fn set_from_path(
+    parent_window: WindowIdentifier,
+    path: &Path,
+    set_on: SetOn,
+    show_preview: bool,
+) -> crate::Result<()> {
+    let mut file = File::open(path)?;
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    (&mut file)
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut header)?;
+    check_image(&header, &path.display().to_string())?;
+
+    let connection = zbus::Connection::new_session()?;
+    let proxy = WallpaperProxy::new(&connection)?;
+    let handle = proxy.set_wallpaper_file(
+        parent_window,
+        Fd::from(file.as_raw_fd()),
+        options(set_on, show_preview),
+    )?;
+    receive_response(&connection, &handle)
+}
+
+/// Sets the wallpaper from an image in memory.
+///
+/// The image is written to a sealed memory file, which is passed to the portal.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `bytes` - The encoded image, e.g. the content of a PNG file
+/// * `set_on` - Where to set the wallpaper on
+/// * `show_preview` - Whether to show a preview of the wallpaper, the portal may show one anyway
+pub fn set_from_bytes(
+    parent_window: WindowIdentifier,
+    bytes: &[u8],
+    set_on: SetOn,
+    show_preview: bool,
+) -> crate::Result<()> {
+    check_image(bytes, "the wallpaper")?;
+    let file = sealed_memfd("wallpaper", bytes)?;
+
+    let connection = zbus::Connection::new_session()?;
+    let proxy = WallpaperProxy::new(&connection)?;
+    let handle = proxy.set_wallpaper_file(
+        parent_window,
+        Fd::from(file.as_raw_fd()),
+        options(set_on, show_preview),
+    )?;
+    receive_response(&connection, &handle)
+}
+
+/// Sets the wallpaper from a URL.
+///
+/// Local files are sent as file descriptors, see [`set_from_path`].
+/// Remote images are downloaded by the portal, their type can't be checked beforehand.
+///
+/// # Arguments
+///
+/// * `parent_window` - Identifier for the application window
+/// * `url` - The URL of the image
+/// * `set_on` - Where to set the wallpaper on
+/// * `show_preview` - Whether to show a preview of the wallpaper, the portal may show one anyway
+///
+/// [`set_from_path`]: ./fn.set_from_path.html
+pub fn set_from_url(
+    parent_window: WindowIdentifier,
+    url: &Url,
+    set_on: SetOn,
+    show_preview: bool,
+) -> crate::Result<()> {
+    if url.scheme() == "file" {
+        let path = url
+            .to_file_path()
+            .map_err(|_| Error::InvalidArgument(format!("{} is not a valid file path", url)))?;
+        return set_from_path(parent_window, &path, set_on, show_preview);
+    }
+
+    let connection = zbus::Connection::new_session()?;
+    let proxy = WallpaperProxy::new(&connection)?;
+    let handle =
+        proxy.set_wallpaper_uri(parent_window, url.as_str(), options(set_on, show_preview))?;
+    receive_response(&connection, &handle)
+}
+
+fn options(set_on: SetOn, show_preview: bool) -> WallpaperOptions {
+    WallpaperOptions::default()
+        .set_on(set_on)
+        .show_preview(show_preview)
+}
+
+fn receive_response(connection: &zbus::Connection, handle: &OwnedObjectPath) -> crate::Result<()> {
+    let request = RequestProxy::new(connection, handle)?;
+    request.receive_response::<BasicResponse>()??;
+    Ok(())
+}
+
+/// The number of bytes needed to recognize the supported image formats.
+const HEADER_LEN: usize = 512;
+
+/// Rejects anything that doesn't look like an image format a desktop can use as a wallpaper.
+fn check_image(header: &[u8], name: &str) -> crate::Result<()> {
+    match image_mime_type(header) {
+        Some(_) => Ok(()),
+        None => Err(Error::InvalidArgument(format!(
+            "{} is not a supported image",
+            name
+        ))),
+    }
+}
+
+/// Guesses the mime type of an image from its first bytes.
+fn image_mime_type(header: &[u8]) -> Option<&'static str> {
+    let mime_type = if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if header.starts_with(b"\xff\xd8\xff") {
+        "image/jpeg"
+    } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+        "image/gif"
+    } else if header.len() >= 12 && &header[..4] == b"RIFF" && &header[8..12] == b"WEBP" {
+        "image/webp"
+    } else if header.starts_with(b"BM") {
+        "image/bmp"
+    } else if header.starts_with(b"II*\0") || header.starts_with(b"MM\0*") {
+        "image/tiff"
+    } else if header.starts_with(b"\xff\x0a")
+        || header.starts_with(b"\0\0\0\x0cJXL \x0d\x0a\x87\x0a")
+    {
+        "image/jxl"
+    } else if header.len() >= 12 && &header[4..8] == b"ftyp" {
+        match &header[8..12] {
+            b"avif" | b"avis" => "image/avif",
+            b"heic" | b"heix" | b"mif1" | b"msf1" => "image/heif",
+            _ => return None,
+        }
+    } else if String::from_utf8_lossy(header).contains("<svg") {
+        "image/svg+xml"
+    } else {
+        return None;
+    };
+    Some(mime_type)
+}
+
+/// Creates a memory backed file containing `bytes`, sealed so it can't be modified anymore.
+fn sealed_memfd(name: &str, bytes: &[u8]) -> io::Result<File> {
+    let name = CString::new(name).unwrap();
+    let fd =
+        unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING) };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    file.write_all(bytes)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    let seals = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE | libc::F_SEAL_SEAL;
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_ADD_SEALS, seals) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(file)
+}
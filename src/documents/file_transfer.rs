@@ -1,39 +1,68 @@
 //! # Examples
 //!
 //! ```no_run
-//! use ashpd::documents::file_transfer::{FileTransferProxy, TransferOptions};
-//! use zbus::{fdo::Result, Connection};
-//! use std::collections::HashMap;
-//! use zvariant::Fd;
-//! use std::fs::File;
-//! use std::os::unix::io::AsRawFd;
+//! use ashpd::documents::file_transfer::{self, TransferOptions};
+//! use std::path::Path;
 //!
-//! fn main() -> Result<()> {
-//!     let connection = Connection::new_session()?;
-//!     let proxy = FileTransferProxy::new(&connection)?;
-//!
-//!     let key = proxy.start_transfer(
+//! fn main() -> ashpd::Result<()> {
+//!     let transfer = file_transfer::start_transfer(
 //!         TransferOptions::default()
 //!             .writeable(true)
 //!             .autostop(true)
 //!     )?;
-//!     let file = File::open("/home/bilelmoussaoui/Downloads/adwaita-night.jpg").unwrap();
-//!     proxy.add_files(&key, &[Fd::from(file.as_raw_fd())], HashMap::new())?;
+//!     transfer.add_path(Path::new("/home/bilelmoussaoui/Downloads/adwaita-night.jpg"))?;
 //!
-//!     // The files would be retrieved by another process
-//!     let files = proxy.retrieve_files(&key, HashMap::new())?;
+//!     // The key would be sent to another process, which retrieves the files
+//!     let files = file_transfer::retrieve(transfer.key())?;
 //!     println!("{:#?}", files);
 //!
-//!     proxy.stop_transfer(&key)?;
+//!     // Resolves once the files were retrieved, as autostop is set
+//!     transfer.wait_closed()?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The same transfers back drag-and-drop and copy-paste of files between sandboxed applications:
+//!
+//! ```no_run
+//! use ashpd::documents::file_transfer::{self, Payload, TransferOptions};
+//! use std::path::Path;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     // On the source side, offer the data for each of `Payload::mime_types()`
+//!     let payload = Payload::new(
+//!         &[Path::new("/home/bilelmoussaoui/Downloads/adwaita-night.jpg")],
+//!         TransferOptions::default(),
+//!     )?;
+//!     let data = payload.data(file_transfer::MIME_TYPE).unwrap();
+//!
+//!     // On the target side, decode the data received for the negotiated mime type
+//!     let files = file_transfer::decode(file_transfer::MIME_TYPE, &data)?;
+//!     println!("{:#?}", files);
 //!
 //!     Ok(())
 //! }
 //! ```
-use std::collections::HashMap;
+use crate::helper::open_o_path;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use url::Url;
 use zbus::{dbus_proxy, fdo::Result};
-use zvariant::{Fd, Value};
+use zvariant::Fd;
 use zvariant_derive::{DeserializeDict, SerializeDict, TypeDict};
 
+/// The mime type carrying the key of a file transfer, for drag-and-drop and copy-paste.
+pub const MIME_TYPE: &str = "application/vnd.portal.filetransfer";
+
+/// The mime type carrying a list of `file://` URIs,
+/// for targets that don't understand [`MIME_TYPE`].
+///
+/// [`MIME_TYPE`]: ./constant.MIME_TYPE.html
+pub const URI_LIST_MIME_TYPE: &str = "text/uri-list";
+
 #[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
 /// Specified options on a start transfer request.
 pub struct TransferOptions {
@@ -57,6 +86,18 @@ impl TransferOptions {
     }
 }
 
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options on an add files request.
+///
+/// The portal doesn't define any option yet.
+pub struct AddFilesOptions {}
+
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+/// Specified options on a retrieve files request.
+///
+/// The portal doesn't define any option yet.
+pub struct RetrieveFilesOptions {}
+
 #[dbus_proxy(
     interface = "org.freedesktop.portal.FileTransfer",
     default_service = "org.freedesktop.portal.Documents",
@@ -81,9 +122,10 @@ trait FileTransfer {
     ///
     /// * `key` - a key returned by `start_transfer`
     /// * `fds` - a list of file descriptors of the files to register
-    /// * `options` - ?
-    /// FIXME: figure out the options we can take here
-    fn add_files(&self, key: &str, fds: &[Fd], options: HashMap<&str, Value>) -> Result<()>;
+    /// * `options` - [`AddFilesOptions`]
+    ///
+    /// [`AddFilesOptions`]: ./struct.AddFilesOptions.html
+    fn add_files(&self, key: &str, fds: &[Fd], options: AddFilesOptions) -> Result<()>;
 
     /// Retrieves files that were previously added to the session with `add_files`.
     /// The files will be exported in the document portal as-needed for the caller,
@@ -94,9 +136,10 @@ trait FileTransfer {
     /// # Arguments
     ///
     /// * `key` - a key returned by `start_transfer`
-    /// * `options` - ?
-    /// FIXME: figure out the options we can take here
-    fn retrieve_files(&self, key: &str, options: HashMap<&str, Value>) -> Result<Vec<String>>;
+    /// * `options` - [`RetrieveFilesOptions`]
+    ///
+    /// [`RetrieveFilesOptions`]: ./struct.RetrieveFilesOptions.html
+    fn retrieve_files(&self, key: &str, options: RetrieveFilesOptions) -> Result<Vec<String>>;
 
     /// Starts a session for a file transfer.
     /// The caller should call `add_files` at least once, to add files to this session.
@@ -112,10 +155,251 @@ trait FileTransfer {
     /// * `key` - A key returned by `start_transfer`
     fn stop_transfer(&self, key: &str) -> Result<()>;
 
-    // FIXME: signal
-    // fn transfer_closed(&self, key: &str);
+    /// Emitted when the transfer is closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the closed transfer
+    #[dbus_proxy(signal)]
+    fn transfer_closed(&self, key: &str) -> Result<()>;
 
     /// version property
     #[dbus_proxy(property, name = "version")]
     fn version(&self) -> Result<u32>;
 }
+
+/// An ongoing file transfer, created by [`start_transfer`].
+///
+/// The key has to be handed to the receiving application, for example
+/// through a drag-and-drop or a clipboard payload, which then calls [`retrieve`].
+///
+/// [`start_transfer`]: ./fn.start_transfer.html
+/// [`retrieve`]: ./fn.retrieve.html
+pub struct Transfer {
+    connection: zbus::Connection,
+    key: String,
+}
+
+impl Transfer {
+    /// The key identifying the transfer.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Adds a file to the transfer.
+    ///
+    /// Only regular files, not directories, can be added.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file
+    pub fn add_path(&self, path: &Path) -> crate::Result<()> {
+        self.add_paths(&[path])
+    }
+
+    /// Adds several files to the transfer at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The paths of the files
+    pub fn add_paths<P: AsRef<Path>>(&self, paths: &[P]) -> crate::Result<()> {
+        let files = paths
+            .iter()
+            .map(|path| open_o_path(path.as_ref()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let fds = files
+            .iter()
+            .map(|file| Fd::from(file.as_raw_fd()))
+            .collect::<Vec<_>>();
+        let proxy = FileTransferProxy::new(&self.connection)?;
+        proxy.add_files(&self.key, &fds, AddFilesOptions::default())?;
+        Ok(())
+    }
+
+    /// Waits until the `transfer_closed` signal is received for this transfer.
+    ///
+    /// The portal closes a transfer when it is stopped, when its files were
+    /// retrieved with `autostop` set, or when the sending application goes away.
+    pub fn wait_closed(&self) -> crate::Result<()> {
+        let proxy = FileTransferProxy::new(&self.connection)?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let key = self.key.clone();
+        let closed_handle = closed.clone();
+        proxy.connect_transfer_closed(move |closed_key| {
+            if closed_key == key {
+                closed_handle.store(true, Ordering::SeqCst);
+            }
+            Ok(())
+        })?;
+        while !closed.load(Ordering::SeqCst) {
+            proxy.next_signal()?;
+        }
+        Ok(())
+    }
+
+    /// Ends the transfer.
+    ///
+    /// The key can no longer be used to retrieve the files afterwards.
+    pub fn stop(self) -> crate::Result<()> {
+        let proxy = FileTransferProxy::new(&self.connection)?;
+        proxy.stop_transfer(&self.key)?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Transfer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transfer").field("key", &self.key).finish()
+    }
+}
+
+/// Starts a file transfer.
+///
+/// Files are then added with [`Transfer::add_path`].
+///
+/// # Arguments
+///
+/// * `options` - A [`TransferOptions`]
+///
+/// [`Transfer::add_path`]: ./struct.Transfer.html#method.add_path
+/// [`TransferOptions`]: ./struct.TransferOptions.html
+pub fn start_transfer(options: TransferOptions) -> crate::Result<Transfer> {
+    let connection = zbus::Connection::new_session()?;
+    let key = FileTransferProxy::new(&connection)?.start_transfer(options)?;
+    Ok(Transfer { connection, key })
+}
+
+/// Retrieves the files of a transfer started by another application.
+///
+/// The files are exported in the document store as needed, so the returned
+/// paths are accessible from within the sandbox.
+///
+/// # Arguments
+///
+/// * `key` - The key of the transfer
+pub fn retrieve(key: &str) -> crate::Result<Vec<PathBuf>> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = FileTransferProxy::new(&connection)?;
+    let files = proxy.retrieve_files(key, RetrieveFilesOptions::default())?;
+    Ok(files.into_iter().map(PathBuf::from).collect())
+}
+
+#[derive(Debug)]
+/// The data offered by the source of a drag-and-drop or copy-paste of files.
+///
+/// The files are made available through a [`Transfer`], whose key is sent as
+/// [`MIME_TYPE`], along with their URIs as [`URI_LIST_MIME_TYPE`] for
+/// targets that don't support the file transfer portal.
+///
+/// [`Transfer`]: ./struct.Transfer.html
+/// [`MIME_TYPE`]: ./constant.MIME_TYPE.html
+/// [`URI_LIST_MIME_TYPE`]: ./constant.URI_LIST_MIME_TYPE.html
+pub struct Payload {
+    transfer: Transfer,
+    uris: Vec<Url>,
+}
+
+impl Payload {
+    /// Starts a transfer of the files and prepares the data to offer.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The absolute paths of the files
+    /// * `options` - A [`TransferOptions`]
+    ///
+    /// [`TransferOptions`]: ./struct.TransferOptions.html
+    pub fn new<P: AsRef<Path>>(paths: &[P], options: TransferOptions) -> crate::Result<Self> {
+        let uris = paths
+            .iter()
+            .map(|path| {
+                let path = path.as_ref();
+                Url::from_file_path(path).map_err(|_| {
+                    crate::Error::InvalidArgument(format!("{} is not absolute", path.display()))
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        let transfer = start_transfer(options)?;
+        transfer.add_paths(paths)?;
+        Ok(Self { transfer, uris })
+    }
+
+    /// The mime types the data is available as, in order of preference.
+    pub fn mime_types() -> &'static [&'static str] {
+        &[MIME_TYPE, URI_LIST_MIME_TYPE]
+    }
+
+    /// The data to send for a mime type, if it is one of [`Payload::mime_types`].
+    ///
+    /// [`Payload::mime_types`]: ./struct.Payload.html#method.mime_types
+    pub fn data(&self, mime_type: &str) -> Option<Vec<u8>> {
+        match mime_type {
+            // Toolkits exchange the key as a nul-terminated string.
+            MIME_TYPE => {
+                let mut data = self.transfer.key().as_bytes().to_vec();
+                data.push(0);
+                Some(data)
+            }
+            URI_LIST_MIME_TYPE => Some(
+                self.uris
+                    .iter()
+                    .map(|uri| format!("{}\r\n", uri))
+                    .collect::<String>()
+                    .into_bytes(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// The underlying transfer.
+    pub fn transfer(&self) -> &Transfer {
+        &self.transfer
+    }
+
+    /// Consumes the payload, returning the underlying transfer,
+    /// to stop it once the drag-and-drop or copy-paste is over.
+    pub fn into_transfer(self) -> Transfer {
+        self.transfer
+    }
+}
+
+/// Decodes the data received by the target of a drag-and-drop or copy-paste of files.
+///
+/// Returns the local paths of the files. The files of a [`MIME_TYPE`] payload are
+/// retrieved from the portal, while the non `file://` URIs of an [`URI_LIST_MIME_TYPE`]
+/// payload are skipped.
+///
+/// # Arguments
+///
+/// * `mime_type` - The mime type of the data, one of [`Payload::mime_types`]
+/// * `data` - The received data
+///
+/// [`MIME_TYPE`]: ./constant.MIME_TYPE.html
+/// [`URI_LIST_MIME_TYPE`]: ./constant.URI_LIST_MIME_TYPE.html
+/// [`Payload::mime_types`]: ./struct.Payload.html#method.mime_types
+pub fn decode(mime_type: &str, data: &[u8]) -> crate::Result<Vec<PathBuf>> {
+    let data = std::str::from_utf8(data)
+        .map_err(|_| crate::Error::InvalidArgument(format!("{} data is not UTF-8", mime_type)))?;
+    match mime_type {
+        MIME_TYPE => {
+            let key = data.trim_end_matches('\0').trim();
+            if key.is_empty() {
+                return Err(crate::Error::InvalidArgument(
+                    "empty file transfer key".to_string(),
+                ));
+            }
+            retrieve(key)
+        }
+        URI_LIST_MIME_TYPE => Ok(data
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| Url::parse(line).ok())
+            .filter(|uri| uri.scheme() == "file")
+            .filter_map(|uri| uri.to_file_path().ok())
+            .collect()),
+        _ => Err(crate::Error::InvalidArgument(format!(
+            "unsupported mime type {}",
+            mime_type
+        ))),
+    }
+}
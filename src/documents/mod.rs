@@ -2,7 +2,7 @@
 //! # Examples
 //!
 //! ```no_run
-//! use ashpd::documents::{DocumentsProxy, Permission};
+//! use ashpd::documents::{DocumentId, DocumentsProxy, Permission};
 //! use zbus::{fdo::Result, Connection};
 //!
 //! fn main() -> Result<()> {
@@ -11,37 +11,93 @@
 //!
 //!     println!("{:#?}", proxy.get_mount_point()?);
 //!
-//!     for (doc_id, host_path) in proxy.list("org.mozilla.firefox")? {
-//!         if doc_id == "f2ee988d" {
+//!     let doc_id = DocumentId::from("f2ee988d");
+//!     for (id, host_path) in proxy.list("org.mozilla.firefox")? {
+//!         if id == doc_id {
 //!             let info = proxy.info(&doc_id)?;
 //!             println!("{:#?}", info);
 //!         }
 //!     }
 //!
 //!     proxy.grant_permissions(
-//!         "f2ee988d",
+//!         &doc_id,
 //!         "org.mozilla.firefox",
 //!         &[Permission::GrantPermissions],
 //!     )?;
-//!     proxy.revoke_permissions("f2ee988d", "org.mozilla.firefox", &[Permission::Write])?;
+//!     proxy.revoke_permissions(&doc_id, "org.mozilla.firefox", &[Permission::Write])?;
+//!     proxy.grant(&doc_id, "org.gnome.Evince", Permission::Read | Permission::Write)?;
 //!
-//!     proxy.delete("f2ee988d")?;
+//!     proxy.delete(&doc_id)?;
 //!
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Export a file and get its path inside the sandbox of an application
+//!
+//! ```no_run
+//! use ashpd::documents::{self, Flags};
+//! use std::path::Path;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     let doc_id = documents::export(
+//!         Path::new("/home/bilelmoussaoui/report.pdf"),
+//!         Flags::ReuseExisting | Flags::Persistent,
+//!     )?;
+//!     println!("{}", documents::exported_path(&doc_id, Some("org.gnome.Evince"))?.display());
+//!     Ok(())
+//! }
+//! ```
+
+mod permission;
+pub use self::permission::{
+    diff_permissions, Permission, PermissionSet, Permissions, PermissionsChange,
+};
+mod store;
+pub use self::store::{Document, DocumentStore};
 
+use crate::helper::open_o_path;
 use crate::NString;
 use enumflags2::BitFlags;
-use serde::{de::Deserializer, Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::collections::HashMap;
-use std::str::FromStr;
-use strum_macros::{AsRefStr, EnumString, IntoStaticStr, ToString};
+use std::fmt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
 use zbus::{dbus_proxy, fdo::Result};
-use zvariant::{Fd, Signature};
+use zvariant::Fd;
 use zvariant_derive::Type;
 
+#[derive(Serialize, Deserialize, Type, Debug, Clone, PartialEq, Eq, Hash)]
+/// The ID of a file in the document store.
+pub struct DocumentId(String);
+
+impl DocumentId {
+    /// The ID as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for DocumentId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl From<String> for DocumentId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for DocumentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Copy, Clone, BitFlags, Debug, Type)]
 #[repr(u32)]
 ///
@@ -56,48 +112,6 @@ pub enum Flags {
     ExportDirectory = 8,
 }
 
-/// A `HashMap` mapping application IDs to the permissions for that application
-pub type Permissions = HashMap<String, Vec<Permission>>;
-
-#[derive(Debug, Clone, AsRefStr, EnumString, IntoStaticStr, ToString, PartialEq, Eq)]
-#[strum(serialize_all = "lowercase")]
-/// The possible permissions to grant to a specific application for a specific document.
-pub enum Permission {
-    /// Read access.
-    Read,
-    /// Write access.
-    Write,
-    #[strum(serialize = "grant-permissions")]
-    /// The possibility to grant new permissions to the file.
-    GrantPermissions,
-    /// Delete access.
-    Delete,
-}
-
-impl zvariant::Type for Permission {
-    fn signature() -> Signature<'static> {
-        Signature::from_string_unchecked("s".to_string())
-    }
-}
-
-impl Serialize for Permission {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        String::serialize(&self.to_string(), serializer)
-    }
-}
-
-impl<'de> Deserialize<'de> for Permission {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        Ok(Permission::from_str(&String::deserialize(deserializer)?).expect("invalid permission"))
-    }
-}
-
 #[dbus_proxy(
     interface = "org.freedesktop.portal.Documents",
     default_service = "org.freedesktop.portal.Documents",
@@ -128,7 +142,7 @@ trait Documents {
     /// * `o_path_fd` - open file descriptor for the file to add
     /// * `reuse_existing` - whether to reuse an existing document store entry for the file
     /// * `persistent` - whether to add the file only for this session or permanently
-    fn add(&self, o_path_fd: Fd, reuse_existing: bool, persistent: bool) -> Result<String>;
+    fn add(&self, o_path_fd: Fd, reuse_existing: bool, persistent: bool) -> Result<DocumentId>;
 
     /// Adds multiple files to the document store.
     /// The files are passed in the form of an open file descriptor
@@ -148,7 +162,7 @@ trait Documents {
         flags: BitFlags<Flags>,
         app_id: &str,
         permissions: &[Permission],
-    ) -> Result<(Vec<String>, HashMap<String, zvariant::OwnedValue>)>;
+    ) -> Result<(Vec<DocumentId>, HashMap<String, zvariant::OwnedValue>)>;
 
     /// Creates an entry in the document store for writing a new file.
     ///
@@ -166,7 +180,7 @@ trait Documents {
         filename: &NString,
         reuse_existing: bool,
         persistent: bool,
-    ) -> Result<String>;
+    ) -> Result<DocumentId>;
 
     /// Adds multiple files to the document store.
     /// The files are passed in the form of an open file descriptor
@@ -188,7 +202,7 @@ trait Documents {
         flags: BitFlags<Flags>,
         app_id: &str,
         permissions: &[Permission],
-    ) -> Result<(String, HashMap<String, zvariant::OwnedValue>)>;
+    ) -> Result<(DocumentId, HashMap<String, zvariant::OwnedValue>)>;
 
     /// Removes an entry from the document store. The file itself is not deleted.
     /// This call is available inside the sandbox if the application
@@ -197,7 +211,20 @@ trait Documents {
     /// # Arguments
     ///
     /// * `doc_id` - The ID of the file in the document store
-    fn delete(&self, doc_id: &str) -> Result<()>;
+    fn delete(&self, doc_id: &DocumentId) -> Result<()>;
+
+    /// Gets the host filesystem paths of document store entries.
+    /// This call is available inside the sandbox for the documents the application has access to.
+    ///
+    /// Returns a `HashMap` mapping document IDs to their filesystem path on the host system,
+    /// the IDs that couldn't be found are left out.
+    ///
+    /// Available since version 5 of the interface.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc_ids` - The IDs of the files in the document store
+    fn get_host_paths(&self, doc_ids: &[DocumentId]) -> Result<HashMap<DocumentId, NString>>;
 
     /// Returns the path at which the document store fuse filesystem is mounted.
     /// This will typically be /run/user/$UID/doc/.
@@ -213,7 +240,7 @@ trait Documents {
     /// * `permissions` - the permissions to grant.
     fn grant_permissions(
         &self,
-        doc_id: &str,
+        doc_id: &DocumentId,
         app_id: &str,
         permissions: &[Permission],
     ) -> Result<()>;
@@ -225,7 +252,7 @@ trait Documents {
     /// # Arguments
     ///
     /// * `doc_id` - The ID of the file in the document store
-    fn info(&self, doc_id: &str) -> Result<(NString, Permissions)>;
+    fn info(&self, doc_id: &DocumentId) -> Result<(NString, Permissions)>;
 
     /// Lists documents in the document store for an application (or for all applications).
     ///
@@ -234,7 +261,7 @@ trait Documents {
     /// # Arguments
     ///
     /// * `app-id` - The application ID, or '' to list all documents
-    fn list(&self, app_id: &str) -> Result<HashMap<String, NString>>;
+    fn list(&self, app_id: &str) -> Result<HashMap<DocumentId, NString>>;
 
     /// Looks up the document ID for a file.
     /// This call is not available inside the sandbox.
@@ -245,7 +272,7 @@ trait Documents {
     /// # Arguments
     ///
     /// - `filename` - A path in the host filesystem
-    fn lookup(&self, filename: NString) -> Result<String>;
+    fn lookup(&self, filename: NString) -> Result<DocumentId>;
 
     /// Revokes access permissions for a file in the document store from an application.
     /// This call is available inside the sandbox if the application
@@ -258,7 +285,7 @@ trait Documents {
     /// * `permissions` - The permissions to revoke.
     fn revoke_permissions(
         &self,
-        doc_id: &str,
+        doc_id: &DocumentId,
         app_id: &str,
         permissions: &[Permission],
     ) -> Result<()>;
@@ -268,5 +295,195 @@ trait Documents {
     fn version(&self) -> Result<u32>;
 }
 
+impl<'a> DocumentsProxy<'a> {
+    /// Grants a set of permissions for a file in the document store to an application,
+    /// see [`DocumentsProxy::grant_permissions`].
+    ///
+    /// # Arguments
+    ///
+    /// * `doc_id` - the ID of the file in the document store.
+    /// * `app_id` - the ID of the application to which permissions are granted.
+    /// * `permissions` - the permissions to grant.
+    ///
+    /// [`DocumentsProxy::grant_permissions`]: #method.grant_permissions
+    pub fn grant(
+        &self,
+        doc_id: &DocumentId,
+        app_id: &str,
+        permissions: impl Into<PermissionSet>,
+    ) -> Result<()> {
+        self.grant_permissions(doc_id, app_id, &permissions.into().to_vec())
+    }
+
+    /// Revokes a set of permissions for a file in the document store from an application,
+    /// see [`DocumentsProxy::revoke_permissions`].
+    ///
+    /// # Arguments
+    ///
+    /// * `doc_id` - The ID of the file in the document store
+    /// * `app_id` - The ID of the application from which permissions are revoked
+    /// * `permissions` - The permissions to revoke.
+    ///
+    /// [`DocumentsProxy::revoke_permissions`]: #method.revoke_permissions
+    pub fn revoke(
+        &self,
+        doc_id: &DocumentId,
+        app_id: &str,
+        permissions: impl Into<PermissionSet>,
+    ) -> Result<()> {
+        self.revoke_permissions(doc_id, app_id, &permissions.into().to_vec())
+    }
+}
+
+/// Adds a file to the document store.
+///
+/// Returns the ID of the file in the document store.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file, or of the directory with [`Flags::ExportDirectory`]
+/// * `flags` - How to export the file
+///
+/// [`Flags::ExportDirectory`]: ./enum.Flags.html#variant.ExportDirectory
+pub fn export(path: &Path, flags: BitFlags<Flags>) -> crate::Result<DocumentId> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = DocumentsProxy::new(&connection)?;
+    let file = open_o_path(path)?;
+    let (doc_ids, _) = proxy.add_full(&[Fd::from(file.as_raw_fd())], flags, "", &[])?;
+    doc_ids
+        .into_iter()
+        .next()
+        .ok_or_else(|| crate::Error::Portal("no document was added".to_string()))
+}
+
+/// Shares a directory and its content with another application.
+///
+/// The directory is exported persistently, reusing its existing document store entry if any.
+///
+/// Returns the path of the directory inside the sandbox of the application.
+///
+/// ```no_run
+/// use ashpd::documents::{self, Permission};
+/// use std::path::Path;
+///
+/// fn main() -> ashpd::Result<()> {
+///     let path = documents::export_directory(
+///         Path::new("/home/bilelmoussaoui/Projects/ashpd"),
+///         "org.example.LanguageServer",
+///         Permission::Read | Permission::Write,
+///     )?;
+///     println!("{}", path.display());
+///     Ok(())
+/// }
+/// ```
+///
+/// # Arguments
+///
+/// * `path` - The path of the directory
+/// * `app_id` - The application to share the directory with
+/// * `permissions` - The permissions to grant to the application
+pub fn export_directory(
+    path: &Path,
+    app_id: &str,
+    permissions: impl Into<PermissionSet>,
+) -> crate::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| crate::Error::InvalidArgument(format!("{} has no name", path.display())))?;
+    let connection = zbus::Connection::new_session()?;
+    let proxy = DocumentsProxy::new(&connection)?;
+    let file = open_o_path(path)?;
+    let (doc_ids, _) = proxy.add_full(
+        &[Fd::from(file.as_raw_fd())],
+        Flags::ReuseExisting | Flags::Persistent | Flags::ExportDirectory,
+        app_id,
+        &permissions.into().to_vec(),
+    )?;
+    let doc_id = doc_ids
+        .into_iter()
+        .next()
+        .ok_or_else(|| crate::Error::Portal("no document was added".to_string()))?;
+    // The fuse filesystem is mounted at the same location inside the sandbox.
+    Ok(PathBuf::from(&proxy.get_mount_point()?)
+        .join(doc_id.as_str())
+        .join(name))
+}
+
+/// The path at which the document store fuse filesystem is mounted,
+/// typically `/run/user/$UID/doc/`.
+pub fn mount_point() -> crate::Result<PathBuf> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = DocumentsProxy::new(&connection)?;
+    Ok(PathBuf::from(&proxy.get_mount_point()?))
+}
+
+/// The path of an exported document in the document store fuse filesystem.
+///
+/// Inside the sandbox of the application, the path is the same with or without the `app_id`.
+/// Outside of it, the `app_id` selects the view of the filesystem restricted to that application.
+///
+/// # Arguments
+///
+/// * `doc_id` - The ID of the file in the document store
+/// * `app_id` - The application to get the path for, or `None` for the path of the caller
+pub fn exported_path(doc_id: &DocumentId, app_id: Option<&str>) -> crate::Result<PathBuf> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = DocumentsProxy::new(&connection)?;
+    let mut path = PathBuf::from(&proxy.get_mount_point()?);
+    if let Some(app_id) = app_id {
+        path.push("by-app");
+        path.push(app_id);
+    }
+    path.push(doc_id.as_str());
+
+    let (host_path, _) = proxy.info(doc_id)?;
+    if let Some(name) = PathBuf::from(&host_path).file_name() {
+        path.push(name);
+    }
+    Ok(path)
+}
+
+/// The path on the host filesystem of a file exposed by the document store fuse filesystem,
+/// like the files selected in a file chooser dialog.
+///
+/// Returns `None` if the path isn't in the document store fuse filesystem,
+/// or if the document store can't be reached.
+///
+/// # Arguments
+///
+/// * `path` - A path like `/run/user/$UID/doc/$DOC_ID/filename`
+pub fn host_path(path: &Path) -> Option<PathBuf> {
+    let connection = zbus::Connection::new_session().ok()?;
+    let proxy = DocumentsProxy::new(&connection).ok()?;
+    let mount_point = PathBuf::from(&proxy.get_mount_point().ok()?);
+
+    let mut components = path.strip_prefix(&mount_point).ok()?.components();
+    let mut doc_id = components.next()?.as_os_str();
+    // Outside of the sandbox, each application has its own view under `by-app/$APP_ID`.
+    if doc_id == "by-app" {
+        components.next()?;
+        doc_id = components.next()?.as_os_str();
+    }
+    let doc_id = DocumentId::from(doc_id.to_str()?);
+
+    let document_path = if proxy.version().unwrap_or_default() >= 5 {
+        PathBuf::from(
+            proxy
+                .get_host_paths(std::slice::from_ref(&doc_id))
+                .ok()?
+                .get(&doc_id)?,
+        )
+    } else {
+        PathBuf::from(&proxy.info(&doc_id).ok()?.0)
+    };
+    // The document itself, file or directory, is shown inside of its ID's directory,
+    // which has no counterpart on the host.
+    let rest = components.as_path();
+    if rest.as_os_str().is_empty() {
+        return None;
+    }
+    Some(document_path.parent()?.join(rest))
+}
+
 /// Interact with `org.freedesktop.portal.FileTransfer` interface.
 pub mod file_transfer;
@@ -0,0 +1,367 @@
+use serde::{de::Deserializer, ser::SerializeSeq, Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, Sub};
+use std::str::FromStr;
+use zvariant::Signature;
+
+/// A `HashMap` mapping application IDs to the permissions for that application
+pub type Permissions = HashMap<String, PermissionSet>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// The possible permissions to grant to a specific application for a specific document.
+pub enum Permission {
+    /// Read access.
+    Read,
+    /// Write access.
+    Write,
+    /// The possibility to grant new permissions to the file.
+    GrantPermissions,
+    /// Delete access.
+    Delete,
+    /// A permission unknown to this version of the library, sent by a newer portal.
+    Unknown(String),
+}
+
+impl Permission {
+    /// The permission as sent to the portal.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::GrantPermissions => "grant-permissions",
+            Self::Delete => "delete",
+            Self::Unknown(permission) => permission,
+        }
+    }
+
+    fn bit(&self) -> u8 {
+        match self {
+            Self::Read => 1,
+            Self::Write => 1 << 1,
+            Self::GrantPermissions => 1 << 2,
+            Self::Delete => 1 << 3,
+            Self::Unknown(_) => 0,
+        }
+    }
+}
+
+impl AsRef<str> for Permission {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = Infallible;
+
+    fn from_str(permission: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match permission {
+            "read" => Self::Read,
+            "write" => Self::Write,
+            "grant-permissions" => Self::GrantPermissions,
+            "delete" => Self::Delete,
+            _ => Self::Unknown(permission.to_string()),
+        })
+    }
+}
+
+impl zvariant::Type for Permission {
+    fn signature() -> Signature<'static> {
+        Signature::from_string_unchecked("s".to_string())
+    }
+}
+
+impl Serialize for Permission {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Permission {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let permission = String::deserialize(deserializer)?;
+        Ok(match Permission::from_str(&permission) {
+            Ok(permission) => permission,
+            Err(never) => match never {},
+        })
+    }
+}
+
+impl BitOr for Permission {
+    type Output = PermissionSet;
+
+    fn bitor(self, other: Self) -> PermissionSet {
+        PermissionSet::from(self) | other
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Eq)]
+/// A set of [`Permission`]s, combined like flags.
+///
+/// ```
+/// use ashpd::documents::{Permission, PermissionSet};
+///
+/// let permissions = Permission::Read | Permission::Write;
+/// assert!(permissions.contains(&Permission::Read));
+/// assert_eq!(permissions - Permission::Write, PermissionSet::from(Permission::Read));
+/// ```
+///
+/// [`Permission`]: ./enum.Permission.html
+pub struct PermissionSet {
+    bits: u8,
+    /// Kept sorted, to compare sets regardless of the order of their permissions.
+    unknown: Vec<String>,
+}
+
+impl PermissionSet {
+    /// An empty set.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// All the permissions known to this version of the library.
+    pub fn all() -> Self {
+        Permission::Read | Permission::Write | Permission::GrantPermissions | Permission::Delete
+    }
+
+    /// Whether the set has no permissions.
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0 && self.unknown.is_empty()
+    }
+
+    /// Whether the set has a permission.
+    pub fn contains(&self, permission: &Permission) -> bool {
+        match permission {
+            Permission::Unknown(permission) => self.unknown.binary_search(permission).is_ok(),
+            permission => self.bits & permission.bit() != 0,
+        }
+    }
+
+    /// Whether the set has all the permissions of another set.
+    pub fn contains_all(&self, other: &Self) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    /// Adds a permission to the set.
+    pub fn insert(&mut self, permission: Permission) {
+        match permission {
+            Permission::Unknown(permission) => {
+                if let Err(index) = self.unknown.binary_search(&permission) {
+                    self.unknown.insert(index, permission);
+                }
+            }
+            permission => self.bits |= permission.bit(),
+        }
+    }
+
+    /// Removes a permission from the set.
+    pub fn remove(&mut self, permission: &Permission) {
+        match permission {
+            Permission::Unknown(permission) => self.unknown.retain(|p| p != permission),
+            permission => self.bits &= !permission.bit(),
+        }
+    }
+
+    /// The permissions in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        other.iter().fold(self.clone(), |mut set, permission| {
+            set.insert(permission);
+            set
+        })
+    }
+
+    /// The permissions in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.iter().filter(|p| other.contains(p)).collect()
+    }
+
+    /// The permissions of this set that aren't in the other one.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.iter().filter(|p| !other.contains(p)).collect()
+    }
+
+    /// The permissions of the set, the known ones first.
+    pub fn iter(&self) -> impl Iterator<Item = Permission> + '_ {
+        [
+            Permission::Read,
+            Permission::Write,
+            Permission::GrantPermissions,
+            Permission::Delete,
+        ]
+        .iter()
+        .filter(move |permission| self.bits & permission.bit() != 0)
+        .cloned()
+        .chain(self.unknown.iter().cloned().map(Permission::Unknown))
+    }
+
+    /// The permissions of the set, as expected by the proxy methods.
+    pub fn to_vec(&self) -> Vec<Permission> {
+        self.iter().collect()
+    }
+}
+
+impl fmt::Debug for PermissionSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl From<Permission> for PermissionSet {
+    fn from(permission: Permission) -> Self {
+        let mut set = Self::empty();
+        set.insert(permission);
+        set
+    }
+}
+
+impl From<&[Permission]> for PermissionSet {
+    fn from(permissions: &[Permission]) -> Self {
+        permissions.iter().cloned().collect()
+    }
+}
+
+impl From<Vec<Permission>> for PermissionSet {
+    fn from(permissions: Vec<Permission>) -> Self {
+        permissions.into_iter().collect()
+    }
+}
+
+impl FromIterator<Permission> for PermissionSet {
+    fn from_iter<I: IntoIterator<Item = Permission>>(permissions: I) -> Self {
+        let mut set = Self::empty();
+        for permission in permissions {
+            set.insert(permission);
+        }
+        set
+    }
+}
+
+impl BitOr for PermissionSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(&other)
+    }
+}
+
+impl BitOr<Permission> for PermissionSet {
+    type Output = Self;
+
+    fn bitor(mut self, permission: Permission) -> Self {
+        self.insert(permission);
+        self
+    }
+}
+
+impl BitAnd for PermissionSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(&other)
+    }
+}
+
+impl Sub for PermissionSet {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(&other)
+    }
+}
+
+impl Sub<Permission> for PermissionSet {
+    type Output = Self;
+
+    fn sub(mut self, permission: Permission) -> Self {
+        self.remove(&permission);
+        self
+    }
+}
+
+impl zvariant::Type for PermissionSet {
+    fn signature() -> Signature<'static> {
+        Vec::<Permission>::signature()
+    }
+}
+
+impl Serialize for PermissionSet {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(None)?;
+        for permission in self.iter() {
+            seq.serialize_element(&permission)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PermissionSet {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<Permission>::deserialize(deserializer)?.into())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The permissions of an application that changed between two [`Permissions`].
+///
+/// [`Permissions`]: ./type.Permissions.html
+pub struct PermissionsChange {
+    /// The permissions the application gained.
+    pub granted: PermissionSet,
+    /// The permissions the application lost.
+    pub revoked: PermissionSet,
+}
+
+/// Compares two [`Permissions`], e.g. the ones of a document before and after an update.
+///
+/// Returns the changes by application ID, the applications whose permissions
+/// are the same are left out.
+///
+/// # Arguments
+///
+/// * `old` - The previous permissions
+/// * `new` - The current permissions
+///
+/// [`Permissions`]: ./type.Permissions.html
+pub fn diff_permissions(
+    old: &Permissions,
+    new: &Permissions,
+) -> HashMap<String, PermissionsChange> {
+    let empty = PermissionSet::empty();
+    old.keys()
+        .chain(new.keys().filter(|app_id| !old.contains_key(*app_id)))
+        .filter_map(|app_id| {
+            let old = old.get(app_id).unwrap_or(&empty);
+            let new = new.get(app_id).unwrap_or(&empty);
+            let change = PermissionsChange {
+                granted: new.difference(old),
+                revoked: old.difference(new),
+            };
+            if change.granted.is_empty() && change.revoked.is_empty() {
+                None
+            } else {
+                Some((app_id.clone(), change))
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,214 @@
+use super::{DocumentId, DocumentsProxy, PermissionSet, Permissions};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+/// A file in the document store, along with the applications it's shared with.
+pub struct Document {
+    id: DocumentId,
+    host_path: PathBuf,
+    permissions: Permissions,
+}
+
+impl Document {
+    /// The ID of the file in the document store.
+    pub fn id(&self) -> &DocumentId {
+        &self.id
+    }
+
+    /// The path of the file in the host filesystem.
+    pub fn host_path(&self) -> &Path {
+        &self.host_path
+    }
+
+    /// The permissions of each application the document is shared with.
+    pub fn permissions(&self) -> &Permissions {
+        &self.permissions
+    }
+
+    /// The permissions of an application, empty if the document isn't shared with it.
+    pub fn app_permissions(&self, app_id: &str) -> PermissionSet {
+        self.permissions.get(app_id).cloned().unwrap_or_default()
+    }
+}
+
+/// A view of the whole document store, to audit and manage which applications
+/// have access to which documents.
+///
+/// Listing the documents isn't available inside the sandbox.
+///
+/// ```no_run
+/// use ashpd::documents::DocumentStore;
+///
+/// fn main() -> ashpd::Result<()> {
+///     let store = DocumentStore::new()?;
+///     for document in store.documents()? {
+///         println!("{}: {:?}", document.host_path().display(), document.permissions());
+///     }
+///
+///     store.revoke_app("org.example.Untrusted")?;
+///     let garbage = store.garbage()?;
+///     // Ask the user to confirm, the files might only be missing for a while.
+///     let deleted = store.collect_garbage(&garbage)?;
+///     println!("Removed {} documents", deleted.len());
+///     Ok(())
+/// }
+/// ```
+pub struct DocumentStore {
+    proxy: DocumentsProxy<'static>,
+}
+
+impl DocumentStore {
+    /// Connects to the document store on the session bus.
+    pub fn new() -> crate::Result<Self> {
+        let connection = zbus::Connection::new_session()?;
+        Ok(Self {
+            proxy: DocumentsProxy::new(&connection)?,
+        })
+    }
+
+    /// The proxy used to talk to the document store.
+    pub fn proxy(&self) -> &DocumentsProxy<'static> {
+        &self.proxy
+    }
+
+    /// All the documents of the store.
+    pub fn documents(&self) -> crate::Result<Vec<Document>> {
+        self.proxy
+            .list("")?
+            .keys()
+            .map(|id| self.document(id))
+            .collect()
+    }
+
+    /// A document of the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc_id` - The ID of the file in the document store
+    pub fn document(&self, doc_id: &DocumentId) -> crate::Result<Document> {
+        let (host_path, permissions) = self.proxy.info(doc_id)?;
+        Ok(Document {
+            id: doc_id.clone(),
+            host_path: PathBuf::from(&host_path),
+            permissions,
+        })
+    }
+
+    /// The documents shared with an application, and its permissions on each of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `app_id` - The application ID
+    pub fn app_grants(&self, app_id: &str) -> crate::Result<HashMap<DocumentId, PermissionSet>> {
+        self.proxy
+            .list(app_id)?
+            .keys()
+            .map(|id| {
+                let permissions = self.document(id)?.app_permissions(app_id);
+                Ok((id.clone(), permissions))
+            })
+            .collect()
+    }
+
+    /// Revokes all the permissions of an application, on every document.
+    ///
+    /// Returns the number of documents the application lost access to.
+    ///
+    /// # Arguments
+    ///
+    /// * `app_id` - The application ID
+    pub fn revoke_app(&self, app_id: &str) -> crate::Result<usize> {
+        let grants = self.app_grants(app_id)?;
+        for (id, permissions) in &grants {
+            self.proxy.revoke(id, app_id, permissions.clone())?;
+        }
+        Ok(grants.len())
+    }
+
+    /// The documents whose file no longer exists on the host, without deleting them.
+    ///
+    /// The documents are checked with [`DocumentStore::is_gone`]. A file can be missing
+    /// only for a while, for example in the middle of an atomic save, so the list is meant
+    /// to be confirmed, by the user or by checking again later, before passing it
+    /// to [`DocumentStore::collect_garbage`].
+    ///
+    /// [`DocumentStore::is_gone`]: ./struct.DocumentStore.html#method.is_gone
+    /// [`DocumentStore::collect_garbage`]: ./struct.DocumentStore.html#method.collect_garbage
+    pub fn garbage(&self) -> crate::Result<Vec<DocumentId>> {
+        Ok(self
+            .proxy
+            .list("")?
+            .into_iter()
+            .filter(|(_, host_path)| Self::is_gone(&PathBuf::from(host_path)))
+            .map(|(id, _)| id)
+            .collect())
+    }
+
+    /// Deletes the given documents, as listed by [`DocumentStore::garbage`]
+    /// and confirmed by the caller.
+    ///
+    /// Each document is checked again right before deleting it,
+    /// the ones whose file is back on the host are kept.
+    ///
+    /// Returns the IDs of the deleted documents.
+    ///
+    /// # Arguments
+    ///
+    /// * `garbage` - The IDs of the documents to delete
+    ///
+    /// [`DocumentStore::garbage`]: ./struct.DocumentStore.html#method.garbage
+    pub fn collect_garbage(&self, garbage: &[DocumentId]) -> crate::Result<Vec<DocumentId>> {
+        let mut deleted = vec![];
+        for id in garbage {
+            if Self::is_gone(self.document(id)?.host_path()) {
+                self.proxy.delete(id)?;
+                deleted.push(id.clone());
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Whether the file of a document is gone from the host.
+    ///
+    /// The file has to be missing from a directory that still exists,
+    /// so that the documents on unmounted removable media, or added with `add_named`
+    /// in a directory that doesn't exist yet, are kept.
+    /// A file at the root of removable media whose mount point directory stays around
+    /// when unmounted is considered gone.
+    ///
+    /// ```
+    /// use ashpd::documents::DocumentStore;
+    /// use std::fs;
+    ///
+    /// let dir = std::env::temp_dir().join(format!("ashpd-store-{}", std::process::id()));
+    /// fs::create_dir_all(&dir).unwrap();
+    /// let path = dir.join("report.odt");
+    ///
+    /// fs::write(&path, b"").unwrap();
+    /// assert!(!DocumentStore::is_gone(&path));
+    ///
+    /// fs::remove_file(&path).unwrap();
+    /// assert!(DocumentStore::is_gone(&path));
+    ///
+    /// // The whole directory is missing, for example on an unmounted drive.
+    /// assert!(!DocumentStore::is_gone(&dir.join("unmounted").join("report.odt")));
+    /// fs::remove_dir(&dir).unwrap();
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `host_path` - The path of the file in the host filesystem
+    pub fn is_gone(host_path: &Path) -> bool {
+        let parent_exists = match host_path.parent() {
+            Some(parent) => parent.is_dir(),
+            None => false,
+        };
+        parent_exists
+            && matches!(
+                host_path.symlink_metadata(),
+                Err(err) if err.kind() == io::ErrorKind::NotFound
+            )
+    }
+}
@@ -0,0 +1,74 @@
+use crate::ResponseError;
+use std::{fmt, io};
+
+/// The result type returned by the helpers that wrap one or more portal calls.
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+/// The error type returned by the helpers that wrap one or more portal calls.
+pub enum Error {
+    /// The user cancelled the request or it was ended in some other way.
+    Response(ResponseError),
+    /// The portal reported that it failed to carry out the operation.
+    Portal(String),
+    /// The requested device or feature isn't available on this system.
+    NotAvailable(String),
+    /// An argument was rejected before calling the portal.
+    InvalidArgument(String),
+    /// A DBus error.
+    Zbus(zbus::fdo::Error),
+    /// An I/O error, while preparing the request or reading its result.
+    IO(io::Error),
+    /// The image returned by the portal couldn't be decoded.
+    #[cfg(feature = "image")]
+    Image(image::ImageError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Response(ResponseError::Cancelled) => write!(f, "The request was cancelled"),
+            Self::Response(ResponseError::Other) => write!(f, "The request was ended"),
+            Self::Portal(message) => write!(f, "Portal error: {}", message),
+            Self::NotAvailable(message) => write!(f, "Not available: {}", message),
+            Self::InvalidArgument(message) => write!(f, "Invalid argument: {}", message),
+            Self::Zbus(e) => write!(f, "DBus error: {}", e),
+            Self::IO(e) => write!(f, "I/O error: {}", e),
+            #[cfg(feature = "image")]
+            Self::Image(e) => write!(f, "Image error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ResponseError> for Error {
+    fn from(e: ResponseError) -> Self {
+        Self::Response(e)
+    }
+}
+
+impl From<zbus::fdo::Error> for Error {
+    fn from(e: zbus::fdo::Error) -> Self {
+        Self::Zbus(e)
+    }
+}
+
+impl From<zbus::Error> for Error {
+    fn from(e: zbus::Error) -> Self {
+        Self::Zbus(e.into())
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::IO(e)
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Self::Image(e)
+    }
+}
@@ -0,0 +1,576 @@
+use super::{capabilities_of, FlatpakProxy, SandboxFlags, SpawnFlags, SpawnOptions};
+use crate::helper::pipe;
+use crate::NString;
+use enumflags2::BitFlags;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::{Arc, Mutex};
+use zvariant::Fd;
+
+#[derive(Debug)]
+enum StdioKind {
+    Inherit,
+    Null,
+    Piped,
+    File(File),
+}
+
+/// Describes what to do with a standard I/O stream of a spawned process,
+/// like [`std::process::Stdio`].
+#[derive(Debug)]
+pub struct Stdio(StdioKind);
+
+impl Stdio {
+    /// The stream is shared with the caller, the default.
+    pub fn inherit() -> Self {
+        Self(StdioKind::Inherit)
+    }
+
+    /// The stream is connected to `/dev/null`.
+    pub fn null() -> Self {
+        Self(StdioKind::Null)
+    }
+
+    /// A pipe is arranged between the caller and the process,
+    /// its end is available on the returned [`Child`].
+    ///
+    /// [`Child`]: ./struct.Child.html
+    pub fn piped() -> Self {
+        Self(StdioKind::Piped)
+    }
+}
+
+impl From<File> for Stdio {
+    fn from(file: File) -> Self {
+        Self(StdioKind::File(file))
+    }
+}
+
+impl Default for Stdio {
+    fn default() -> Self {
+        Self::inherit()
+    }
+}
+
+/// The file descriptors passed for the standard I/O streams of a process,
+/// and the ends of the pipes kept by the caller.
+struct StdioFds {
+    fds: HashMap<u32, Fd>,
+    // The files backing `fds`, which have to stay open until the process is spawned.
+    _passed: Vec<File>,
+    stdin: Option<File>,
+    stdout: Option<File>,
+    stderr: Option<File>,
+}
+
+impl StdioFds {
+    fn new(stdin: &Stdio, stdout: &Stdio, stderr: &Stdio) -> std::io::Result<Self> {
+        let mut stdio_fds = Self {
+            fds: HashMap::new(),
+            _passed: Vec::new(),
+            stdin: None,
+            stdout: None,
+            stderr: None,
+        };
+        stdio_fds.stdin = stdio_fds.add(0, stdin)?;
+        stdio_fds.stdout = stdio_fds.add(1, stdout)?;
+        stdio_fds.stderr = stdio_fds.add(2, stderr)?;
+        Ok(stdio_fds)
+    }
+
+    fn add(&mut self, target: u32, stdio: &Stdio) -> std::io::Result<Option<File>> {
+        let is_input = target == 0;
+        let (passed, kept) = match &stdio.0 {
+            StdioKind::Inherit => {
+                self.fds.insert(target, Fd::from(target as i32));
+                return Ok(None);
+            }
+            StdioKind::File(file) => {
+                self.fds.insert(target, Fd::from(file.as_raw_fd()));
+                return Ok(None);
+            }
+            StdioKind::Null => (
+                OpenOptions::new()
+                    .read(is_input)
+                    .write(!is_input)
+                    .open("/dev/null")?,
+                None,
+            ),
+            StdioKind::Piped => {
+                let (reader, writer) = pipe()?;
+                if is_input {
+                    (reader, Some(writer))
+                } else {
+                    (writer, Some(reader))
+                }
+            }
+        };
+        self.fds.insert(target, Fd::from(passed.as_raw_fd()));
+        self._passed.push(passed);
+        Ok(kept)
+    }
+}
+
+fn signal_number(signal: i32) -> crate::Result<u32> {
+    u32::try_from(signal)
+        .map_err(|_| crate::Error::InvalidArgument(format!("invalid signal {}", signal)))
+}
+
+/// What is known about a spawned process from the signals of the portal.
+///
+/// Only the backends of this crate can read and update it.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct ChildState {
+    started: bool,
+    relative_pid: Option<u32>,
+    status: Option<ExitStatus>,
+}
+
+impl ChildState {
+    /// Records the exit status of the process, from the wait status of `waitpid(2)`.
+    pub(crate) fn exited(&mut self, exit_status: u32) {
+        self.status = Some(ExitStatus::from_raw(exit_status as i32));
+    }
+}
+
+pub(super) mod private {
+    /// Keeps `SpawnBackend` implemented by the backends of this crate only.
+    pub trait Sealed {}
+}
+
+/// A portal spawning the processes of a [`CommandBuilder`] and sending them signals.
+///
+/// It is implemented by [`FlatpakSpawn`] for a new instance of the application,
+/// and by [`HostCommand`] for a command running on the host.
+/// It can't be implemented outside of this crate.
+///
+/// [`CommandBuilder`]: ./struct.CommandBuilder.html
+/// [`FlatpakSpawn`]: ./struct.FlatpakSpawn.html
+/// [`HostCommand`]: ./development/struct.HostCommand.html
+pub trait SpawnBackend: private::Sealed + fmt::Debug {
+    /// Clears the environment inherited by the process.
+    #[doc(hidden)]
+    fn clear_env(&mut self);
+
+    /// Spawns the process, returning its PID.
+    #[doc(hidden)]
+    fn spawn(
+        &self,
+        connection: &zbus::Connection,
+        cwd_path: NString,
+        argv: Vec<NString>,
+        fds: HashMap<u32, Fd>,
+        envs: HashMap<&str, &str>,
+        state: &mut ChildState,
+    ) -> crate::Result<u32>;
+
+    /// Sends a Unix signal to the process, or to its process group.
+    #[doc(hidden)]
+    fn signal(
+        connection: &zbus::Connection,
+        pid: u32,
+        signal: u32,
+        to_process_group: bool,
+    ) -> crate::Result<()>;
+
+    /// Connects to the signals of the portal about the process,
+    /// returning the proxy receiving them.
+    #[doc(hidden)]
+    fn watch(
+        connection: &zbus::Connection,
+        pid: u32,
+        state: &Arc<Mutex<ChildState>>,
+    ) -> crate::Result<zbus::Proxy<'static>>;
+}
+
+/// A process builder modeled after [`std::process::Command`], spawning the process
+/// through a [`SpawnBackend`].
+///
+/// See [`Command`] and [`development::Command`].
+///
+/// [`SpawnBackend`]: ./trait.SpawnBackend.html
+/// [`Command`]: ./type.Command.html
+/// [`development::Command`]: ./development/type.Command.html
+#[derive(Debug)]
+pub struct CommandBuilder<B> {
+    program: OsString,
+    args: Vec<OsString>,
+    envs: HashMap<String, String>,
+    current_dir: Option<PathBuf>,
+    stdin: Stdio,
+    stdout: Stdio,
+    stderr: Stdio,
+    backend: B,
+}
+
+impl<B: SpawnBackend> CommandBuilder<B> {
+    pub(crate) fn with_backend(program: impl AsRef<OsStr>, backend: B) -> Self {
+        Self {
+            program: program.as_ref().to_os_string(),
+            args: Vec::new(),
+            envs: HashMap::new(),
+            current_dir: None,
+            stdin: Stdio::default(),
+            stdout: Stdio::default(),
+            stderr: Stdio::default(),
+            backend,
+        }
+    }
+
+    pub(crate) fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Adds an argument to pass to the program.
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    /// Adds several arguments to pass to the program.
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_os_string()));
+        self
+    }
+
+    /// Sets an environment variable of the process.
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.envs.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets several environment variables of the process.
+    pub fn envs<'a, I>(mut self, envs: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        self.envs.extend(
+            envs.into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string())),
+        );
+        self
+    }
+
+    /// Clears the inherited environment, keeping only the variables set with [`env`].
+    ///
+    /// [`env`]: #method.env
+    pub fn env_clear(mut self) -> Self {
+        self.backend.clear_env();
+        self
+    }
+
+    /// Sets the working directory of the process, defaults to the one of the caller.
+    ///
+    /// For a command running on the host, the directory has to exist on the host too.
+    pub fn current_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.current_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the standard input of the process.
+    pub fn stdin(mut self, stdin: Stdio) -> Self {
+        self.stdin = stdin;
+        self
+    }
+
+    /// Sets the standard output of the process.
+    pub fn stdout(mut self, stdout: Stdio) -> Self {
+        self.stdout = stdout;
+        self
+    }
+
+    /// Sets the standard error of the process.
+    pub fn stderr(mut self, stderr: Stdio) -> Self {
+        self.stderr = stderr;
+        self
+    }
+
+    /// Spawns the process, returning a handle to it.
+    pub fn spawn(&self) -> crate::Result<ChildProcess<B>> {
+        let connection = zbus::Connection::new_session()?;
+
+        let cwd_path = match &self.current_dir {
+            Some(dir) => dir.clone(),
+            None => std::env::current_dir()?,
+        };
+        let mut argv = vec![NString::from(self.program.as_os_str())];
+        argv.extend(self.args.iter().map(|arg| NString::from(arg.as_os_str())));
+        let envs = self
+            .envs
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        let stdio = StdioFds::new(&self.stdin, &self.stdout, &self.stderr)?;
+
+        let mut state = ChildState::default();
+        let pid = self.backend.spawn(
+            &connection,
+            cwd_path.as_path().into(),
+            argv,
+            stdio.fds,
+            envs,
+            &mut state,
+        )?;
+        Ok(ChildProcess {
+            connection,
+            pid,
+            state: Arc::new(Mutex::new(state)),
+            backend: std::marker::PhantomData,
+            stdin: stdio.stdin,
+            stdout: stdio.stdout,
+            stderr: stdio.stderr,
+        })
+    }
+}
+
+/// A process spawned by a [`CommandBuilder`].
+///
+/// See [`Child`] and [`development::Child`].
+///
+/// [`CommandBuilder`]: ./struct.CommandBuilder.html
+/// [`Child`]: ./type.Child.html
+/// [`development::Child`]: ./development/type.Child.html
+pub struct ChildProcess<B> {
+    connection: zbus::Connection,
+    pid: u32,
+    state: Arc<Mutex<ChildState>>,
+    backend: std::marker::PhantomData<B>,
+    /// The caller end of the standard input, if it was [`Stdio::piped`].
+    ///
+    /// [`Stdio::piped`]: ./struct.Stdio.html#method.piped
+    pub stdin: Option<File>,
+    /// The caller end of the standard output, if it was [`Stdio::piped`].
+    ///
+    /// [`Stdio::piped`]: ./struct.Stdio.html#method.piped
+    pub stdout: Option<File>,
+    /// The caller end of the standard error, if it was [`Stdio::piped`].
+    ///
+    /// [`Stdio::piped`]: ./struct.Stdio.html#method.piped
+    pub stderr: Option<File>,
+}
+
+impl<B: SpawnBackend> ChildProcess<B> {
+    /// The PID of the process, as returned by the portal.
+    pub fn id(&self) -> u32 {
+        self.pid
+    }
+
+    /// Sends a Unix signal to the process.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal` - The signal to send, for example `libc::SIGTERM`
+    pub fn kill(&self, signal: i32) -> crate::Result<()> {
+        B::signal(&self.connection, self.pid, signal_number(signal)?, false)
+    }
+
+    /// Sends a Unix signal to the process group of the process.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal` - The signal to send, for example `libc::SIGTERM`
+    pub fn kill_process_group(&self, signal: i32) -> crate::Result<()> {
+        B::signal(&self.connection, self.pid, signal_number(signal)?, true)
+    }
+
+    /// Waits for the process to exit, returning its exit status.
+    ///
+    /// The standard input is closed before waiting, so that a process reading it doesn't block.
+    pub fn wait(&mut self) -> crate::Result<ExitStatus> {
+        drop(self.stdin.take());
+        self.wait_for(|state| state.status.is_some())?;
+        Ok(self.state.lock().unwrap().status.unwrap())
+    }
+
+    fn wait_for(&self, done: impl Fn(&ChildState) -> bool) -> crate::Result<()> {
+        if done(&self.state.lock().unwrap()) {
+            return Ok(());
+        }
+        let proxy = B::watch(&self.connection, self.pid, &self.state)?;
+        while !done(&self.state.lock().unwrap()) {
+            proxy.next_signal()?;
+        }
+        Ok(())
+    }
+}
+
+impl<B> fmt::Debug for ChildProcess<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Child")
+            .field("pid", &self.pid)
+            .field("stdin", &self.stdin)
+            .field("stdout", &self.stdout)
+            .field("stderr", &self.stderr)
+            .finish()
+    }
+}
+
+/// Spawns a new instance of the application with the Flatpak portal,
+/// optionally in a tighter sandbox.
+#[derive(Debug, Default)]
+pub struct FlatpakSpawn {
+    flags: BitFlags<SpawnFlags>,
+    options: SpawnOptions,
+}
+
+impl private::Sealed for FlatpakSpawn {}
+
+impl SpawnBackend for FlatpakSpawn {
+    fn clear_env(&mut self) {
+        self.flags |= SpawnFlags::ClearEnv;
+    }
+
+    /// Flags and options that the host can't honor are rejected before calling the portal,
+    /// see [`Capabilities::check`].
+    ///
+    /// [`Capabilities::check`]: ./struct.Capabilities.html#method.check
+    fn spawn(
+        &self,
+        connection: &zbus::Connection,
+        cwd_path: NString,
+        argv: Vec<NString>,
+        fds: HashMap<u32, Fd>,
+        envs: HashMap<&str, &str>,
+        state: &mut ChildState,
+    ) -> crate::Result<u32> {
+        let proxy = FlatpakProxy::new(connection)?;
+        let capabilities = capabilities_of(&proxy)?;
+        let mut flags = self.flags;
+        if capabilities.notify_start() {
+            flags |= SpawnFlags::Emit;
+        }
+        capabilities.check(flags, &self.options)?;
+
+        let pid = proxy.spawn(cwd_path, argv, fds, envs, flags, self.options.clone())?;
+        // Without the `spawn_started` signal, there is no relative PID to wait for.
+        state.started = !flags.contains(SpawnFlags::Emit);
+        Ok(pid)
+    }
+
+    fn signal(
+        connection: &zbus::Connection,
+        pid: u32,
+        signal: u32,
+        to_process_group: bool,
+    ) -> crate::Result<()> {
+        FlatpakProxy::new(connection)?.spawn_signal(pid, signal, to_process_group)?;
+        Ok(())
+    }
+
+    fn watch(
+        connection: &zbus::Connection,
+        pid: u32,
+        state: &Arc<Mutex<ChildState>>,
+    ) -> crate::Result<zbus::Proxy<'static>> {
+        let proxy = FlatpakProxy::new(connection)?;
+        let started_state = state.clone();
+        proxy.connect_spawn_started(move |started_pid, relative_pid| {
+            if started_pid == pid {
+                let mut state = started_state.lock().unwrap();
+                state.started = true;
+                state.relative_pid = Some(relative_pid).filter(|relative_pid| *relative_pid != 0);
+            }
+            Ok(())
+        })?;
+        let exited_state = state.clone();
+        proxy.connect_spawn_exited(move |exited_pid, exit_status| {
+            if exited_pid == pid {
+                exited_state.lock().unwrap().exited(exit_status);
+            }
+            Ok(())
+        })?;
+        Ok(proxy.into_inner())
+    }
+}
+
+/// A process builder spawning a new instance of the application, optionally
+/// in a tighter sandbox, modeled after [`std::process::Command`].
+///
+/// ```no_run
+/// use ashpd::flatpak::{Command, SandboxFlags, Stdio};
+/// use std::io::Read;
+///
+/// fn main() -> ashpd::Result<()> {
+///     let mut child = Command::new("contrast")
+///         .arg("--version")
+///         .env("LANG", "C")
+///         .sandbox(SandboxFlags::DisplayAccess.into())
+///         .stdout(Stdio::piped())
+///         .spawn()?;
+///
+///     let mut output = String::new();
+///     child.stdout.take().unwrap().read_to_string(&mut output)?;
+///     let status = child.wait()?;
+///     println!("{} exited with {}", output, status);
+///     Ok(())
+/// }
+/// ```
+///
+/// Flags and options that the host can't honor are rejected by [`spawn`] before calling
+/// the portal, see [`Capabilities::check`].
+///
+/// [`spawn`]: ./struct.CommandBuilder.html#method.spawn
+/// [`Capabilities::check`]: ./struct.Capabilities.html#method.check
+pub type Command = CommandBuilder<FlatpakSpawn>;
+
+impl CommandBuilder<FlatpakSpawn> {
+    /// Creates a new command for launching `program`, a path inside the new sandbox.
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self::with_backend(program, FlatpakSpawn::default())
+    }
+
+    /// Runs the process in a sandbox, sharing only the given accesses with it.
+    pub fn sandbox(mut self, sandbox_flags: BitFlags<SandboxFlags>) -> Self {
+        self.backend.flags |= SpawnFlags::Sandbox;
+        self.backend.options.sandbox_flags = Some(sandbox_flags);
+        self
+    }
+
+    /// Adds [`SpawnFlags`] to the spawn request.
+    ///
+    /// [`SpawnFlags`]: ./enum.SpawnFlags.html
+    pub fn flags(mut self, flags: BitFlags<SpawnFlags>) -> Self {
+        self.backend.flags |= flags;
+        self
+    }
+
+    /// Sets the [`SpawnOptions`] of the spawn request.
+    ///
+    /// [`SpawnOptions`]: ./struct.SpawnOptions.html
+    pub fn options(mut self, options: SpawnOptions) -> Self {
+        self.backend.options = options;
+        self
+    }
+}
+
+/// A process spawned by a [`Command`].
+///
+/// [`Command`]: ./type.Command.html
+pub type Child = ChildProcess<FlatpakSpawn>;
+
+impl ChildProcess<FlatpakSpawn> {
+    /// The PID of the process relative to the caller's sandbox,
+    /// waiting for the `spawn_started` signal if needed.
+    ///
+    /// It is only known when the sandbox pids are exposed to the caller
+    /// with [`SpawnFlags::Expose`], and if the process didn't exit before.
+    ///
+    /// [`SpawnFlags::Expose`]: ./enum.SpawnFlags.html#variant.Expose
+    pub fn relative_id(&self) -> crate::Result<Option<u32>> {
+        self.wait_for(|state| state.started || state.status.is_some())?;
+        Ok(self.state.lock().unwrap().relative_pid)
+    }
+}
@@ -0,0 +1,178 @@
+//! # Examples
+//!
+//! Run a command on the host, like `flatpak-spawn --host` does.
+//! Only available for Flatpak applications with the `--talk-name=org.freedesktop.Flatpak` permission.
+//!
+//! ```no_run
+//! use ashpd::flatpak::development::Command;
+//! use ashpd::flatpak::Stdio;
+//! use std::io::Read;
+//!
+//! fn main() -> ashpd::Result<()> {
+//!     let mut child = Command::new("git")
+//!         .args(&["status", "--short"])
+//!         .current_dir("/home/bilelmoussaoui/Projects/ashpd")
+//!         .stdout(Stdio::piped())
+//!         .spawn()?;
+//!
+//!     let mut output = String::new();
+//!     child.stdout.take().unwrap().read_to_string(&mut output)?;
+//!     let status = child.wait()?;
+//!     println!("{} exited with {}", output, status);
+//!
+//!     Ok(())
+//! }
+//! ```
+use super::command::{private::Sealed, ChildState};
+use super::{ChildProcess, CommandBuilder, SpawnBackend};
+use crate::NString;
+use enumflags2::BitFlags;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::sync::{Arc, Mutex};
+use zbus::{dbus_proxy, fdo::Result};
+use zvariant::Fd;
+use zvariant_derive::Type;
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Copy, Clone, BitFlags, Debug, Type)]
+#[repr(u32)]
+/// Flags affecting the running of a command on the host.
+pub enum HostCommandFlags {
+    /// Clear the environment.
+    ClearEnv = 1,
+    /// Kill the process when the caller disappears from the session bus.
+    WatchBus = 2,
+}
+
+#[dbus_proxy(
+    interface = "org.freedesktop.Flatpak.Development",
+    default_service = "org.freedesktop.Flatpak",
+    default_path = "/org/freedesktop/Flatpak/Development"
+)]
+/// The interface exposes some interactions with the host, intended for development tools.
+///
+/// As it allows running arbitrary commands outside of the sandbox, it is only available
+/// to applications allowed to talk to `org.freedesktop.Flatpak` on the session bus.
+trait Development {
+    /// This methods let you run a command on the host, outside of the sandbox.
+    ///
+    /// Returns the PID of the new process
+    ///
+    /// # Arguments
+    ///
+    /// * `cwd_path` - the working directory for the new process
+    /// * `argv` - the argv for the new process, starting with the executable to launch
+    /// * `fds` - Array of file descriptors to pass to the new process
+    /// * `envs` - Array of variable/value pairs for the environment of the new process
+    /// * `flags` - A [`HostCommandFlags`]
+    ///
+    /// [`HostCommandFlags`]: ./enum.HostCommandFlags.html
+    fn host_command(
+        &self,
+        cwd_path: NString,
+        argv: Vec<NString>,
+        fds: HashMap<u32, Fd>,
+        envs: HashMap<&str, &str>,
+        flags: BitFlags<HostCommandFlags>,
+    ) -> Result<u32>;
+
+    /// This methods let you send a Unix signal to a process that was started with `host_command`
+    ///
+    /// # Arguments
+    ///
+    /// * `pid` - the PID of the process to send the signal to
+    /// * `signal` - the signal to send
+    /// * `to_process_group` - whether to send the signal to the process group
+    fn host_command_signal(&self, pid: u32, signal: u32, to_process_group: bool) -> Result<()>;
+
+    /// Emitted when a process started by `host_command` exits.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid` - the PID of the process, as returned by `host_command`
+    /// * `exit_status` - the wait status of the process, see `waitpid(2)`
+    #[dbus_proxy(signal)]
+    fn host_command_exited(&self, pid: u32, exit_status: u32) -> Result<()>;
+
+    /// version property
+    #[dbus_proxy(property, name = "version")]
+    fn version(&self) -> Result<u32>;
+}
+
+/// Runs a command on the host with the Development interface of Flatpak.
+#[derive(Debug, Default)]
+pub struct HostCommand {
+    flags: BitFlags<HostCommandFlags>,
+}
+
+impl Sealed for HostCommand {}
+
+impl SpawnBackend for HostCommand {
+    fn clear_env(&mut self) {
+        self.flags |= HostCommandFlags::ClearEnv;
+    }
+
+    fn spawn(
+        &self,
+        connection: &zbus::Connection,
+        cwd_path: NString,
+        argv: Vec<NString>,
+        fds: HashMap<u32, Fd>,
+        envs: HashMap<&str, &str>,
+        _state: &mut ChildState,
+    ) -> crate::Result<u32> {
+        let proxy = DevelopmentProxy::new(connection)?;
+        Ok(proxy.host_command(cwd_path, argv, fds, envs, self.flags)?)
+    }
+
+    fn signal(
+        connection: &zbus::Connection,
+        pid: u32,
+        signal: u32,
+        to_process_group: bool,
+    ) -> crate::Result<()> {
+        DevelopmentProxy::new(connection)?.host_command_signal(pid, signal, to_process_group)?;
+        Ok(())
+    }
+
+    fn watch(
+        connection: &zbus::Connection,
+        pid: u32,
+        state: &Arc<Mutex<ChildState>>,
+    ) -> crate::Result<zbus::Proxy<'static>> {
+        let proxy = DevelopmentProxy::new(connection)?;
+        let state = state.clone();
+        proxy.connect_host_command_exited(move |exited_pid, exit_status| {
+            if exited_pid == pid {
+                state.lock().unwrap().exited(exit_status);
+            }
+            Ok(())
+        })?;
+        Ok(proxy.into_inner())
+    }
+}
+
+/// A process builder running a command on the host,
+/// modeled after [`std::process::Command`] like [`flatpak::Command`].
+///
+/// [`flatpak::Command`]: ../type.Command.html
+pub type Command = CommandBuilder<HostCommand>;
+
+impl CommandBuilder<HostCommand> {
+    /// Creates a new command for launching `program`, looked up in the `PATH` of the host.
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self::with_backend(program, HostCommand::default())
+    }
+
+    /// Kills the process when the caller disappears from the session bus.
+    pub fn kill_on_exit(mut self) -> Self {
+        self.backend_mut().flags |= HostCommandFlags::WatchBus;
+        self
+    }
+}
+
+/// A process running on the host, started by a [`Command`].
+///
+/// [`Command`]: ./type.Command.html
+pub type Child = ChildProcess<HostCommand>;
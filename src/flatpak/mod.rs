@@ -3,28 +3,26 @@
 //! Spawn a process outside of the sandbox, only works in a Flatpak.
 //!
 //! ```no_run
-//! use enumflags2::BitFlags;
-//! use ashpd::flatpak::{FlatpakProxy, SpawnFlags, SpawnOptions};
-//! use zbus::{fdo::Result, Connection};
-//! use std::collections::HashMap;
+//! use ashpd::flatpak::{Command, SpawnFlags};
 //!
-//! fn main() -> Result<()> {
-//!     let connection = Connection::new_session()?;
-//!     let proxy = FlatpakProxy::new(&connection)?;
+//! fn main() -> ashpd::Result<()> {
+//!     let mut child = Command::new("contrast")
+//!         .env_clear()
+//!         .flags(SpawnFlags::NoNetwork.into())
+//!         .spawn()?;
 //!
-//!     proxy.spawn(
-//!         "contrast".into(),
-//!         vec!["".into()],
-//!         HashMap::new(),
-//!         HashMap::new(),
-//!         SpawnFlags::ClearEnv | SpawnFlags::NoNetwork,
-//!         SpawnOptions::default(),
-//!     )?;
+//!     let status = child.wait()?;
+//!     println!("{}", status);
 //!
 //!     Ok(())
 //! }
 //! ```
-use crate::NString;
+mod command;
+pub use self::command::{
+    Child, ChildProcess, Command, CommandBuilder, FlatpakSpawn, SpawnBackend, Stdio,
+};
+
+use crate::{Error, NString};
 use enumflags2::BitFlags;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::collections::HashMap;
@@ -66,31 +64,47 @@ pub enum SpawnFlags {
     Expose = 32,
     /// Emit a SpawnStarted signal once the sandboxed process has been fully started.
     Emit = 64,
+    /// Share the sandbox pid namespace with the caller, see the support property.
+    SharePids = 128,
+    /// Don't provide app files at /app in the new sandbox.
+    EmptyApp = 256,
 }
 
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Copy, Clone, BitFlags, Debug, Type)]
 #[repr(u32)]
 /// Flags marking what optional features are available.
 pub enum SupportsFlags {
-    /// Supports the expose sandbox pids flag of Spawn.
+    /// Supports the expose sandbox pids flag of Spawn,
+    /// and the share sandbox pids one since version 5.
     ExposePids = 1,
 }
 
-#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
+#[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default, Clone)]
 /// Specficied options on a spawn request.
 pub struct SpawnOptions {
     /// A list of filenames for files inside the sandbox that will be exposed to the new sandbox, for reading and writing.
     /// Note that absolute paths or subdirectories are not allowed.
+    #[zvariant(rename = "sandbox-expose")]
     pub sandbox_expose: Option<Vec<String>>,
     /// A list of filenames for files inside the sandbox that will be exposed to the new sandbox, readonly.
     /// Note that absolute paths or subdirectories are not allowed.
+    #[zvariant(rename = "sandbox-expose-ro")]
     pub sandbox_expose_ro: Option<Vec<String>>,
     /// A list of file descriptor for files inside the sandbox that will be exposed to the new sandbox, for reading and writing.
+    #[zvariant(rename = "sandbox-expose-fd")]
     pub sandbox_expose_fd: Option<Vec<Fd>>,
     /// A list of file descriptor for files inside the sandbox that will be exposed to the new sandbox, readonly.
+    #[zvariant(rename = "sandbox-expose-fd-ro")]
     pub sandbox_expose_fd_ro: Option<Vec<Fd>>,
     /// Flags affecting the created sandbox.
+    #[zvariant(rename = "sandbox-flags")]
     pub sandbox_flags: Option<BitFlags<SandboxFlags>>,
+    /// A file descriptor of a directory to mount at /usr in the new sandbox.
+    #[zvariant(rename = "usr-fd")]
+    pub usr_fd: Option<Fd>,
+    /// A file descriptor of a directory to mount at /app in the new sandbox.
+    #[zvariant(rename = "app-fd")]
+    pub app_fd: Option<Fd>,
 }
 
 impl SpawnOptions {
@@ -123,6 +137,26 @@ impl SpawnOptions {
         self.sandbox_flags = Some(sandbox_flags);
         self
     }
+
+    /// Sets the file descriptor of a directory to use as /usr in the new sandbox.
+    pub fn usr_fd(mut self, usr_fd: Fd) -> Self {
+        self.usr_fd = Some(usr_fd);
+        self
+    }
+
+    /// Sets the file descriptor of a directory to use as /app in the new sandbox.
+    pub fn app_fd(mut self, app_fd: Fd) -> Self {
+        self.app_fd = Some(app_fd);
+        self
+    }
+
+    fn uses_sandbox(&self) -> bool {
+        self.sandbox_expose.is_some()
+            || self.sandbox_expose_ro.is_some()
+            || self.sandbox_expose_fd.is_some()
+            || self.sandbox_expose_fd_ro.is_some()
+            || self.sandbox_flags.is_some()
+    }
 }
 
 #[derive(SerializeDict, DeserializeDict, TypeDict, Debug, Default)]
@@ -176,20 +210,177 @@ trait Flatpak {
     /// * `to_process_group` - whether to send the signal to the process group
     fn spawn_signal(&self, pid: u32, signal: u32, to_process_group: bool) -> Result<()>;
 
-    // FIXME: signal
-    // fn spawn_started(&self, pid: u32, relpid: u32);
+    /// Emitted when a process started by `spawn` has been fully started,
+    /// if the [`SpawnFlags::Emit`] flag was set.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid` - the PID of the process, as returned by `spawn`
+    /// * `relpid` - the PID of the process relative to the caller's sandbox,
+    ///     or zero if it isn't known
+    ///
+    /// [`SpawnFlags::Emit`]: ./enum.SpawnFlags.html#variant.Emit
+    #[dbus_proxy(signal)]
+    fn spawn_started(&self, pid: u32, relpid: u32) -> Result<()>;
 
-    // FIXME: signal
-    // fn spawn_existed(&self, pid: u32, exit_status: u32);
+    /// Emitted when a process started by `spawn` exits.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid` - the PID of the process, as returned by `spawn`
+    /// * `exit_status` - the wait status of the process, see `waitpid(2)`
+    #[dbus_proxy(signal)]
+    fn spawn_exited(&self, pid: u32, exit_status: u32) -> Result<()>;
 
-    /// Flags marking what optional features are available.
-    #[dbus_proxy(property)]
-    fn supports(&self) -> Result<u32>;
+    /// Flags marking what optional features are available, see [`FlatpakProxy::supports`].
+    ///
+    /// [`FlatpakProxy::supports`]: ./struct.FlatpakProxy.html#method.supports
+    #[dbus_proxy(property, name = "supports")]
+    fn supports_bits(&self) -> Result<u32>;
 
     /// version property
     #[dbus_proxy(property, name = "version")]
     fn version(&self) -> Result<u32>;
 }
 
+impl<'a> FlatpakProxy<'a> {
+    /// Flags marking what optional features are available.
+    pub fn supports(&self) -> Result<BitFlags<SupportsFlags>> {
+        Ok(BitFlags::from_bits_truncate(self.supports_bits()?))
+    }
+}
+
+/// The features of the Flatpak portal available on the host,
+/// depending on its version and on how Flatpak is set up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capabilities {
+    version: u32,
+    supports: BitFlags<SupportsFlags>,
+}
+
+impl Capabilities {
+    /// The version of the portal.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The optional features reported by the portal.
+    pub fn supports(&self) -> BitFlags<SupportsFlags> {
+        self.supports
+    }
+
+    /// Whether the pids of a new sandbox can be exposed to the caller, with [`SpawnFlags::Expose`].
+    ///
+    /// [`SpawnFlags::Expose`]: ./enum.SpawnFlags.html#variant.Expose
+    pub fn expose_pids(&self) -> bool {
+        self.version >= 3 && self.supports.contains(SupportsFlags::ExposePids)
+    }
+
+    /// Whether a new sandbox can share the pid namespace of the caller, with [`SpawnFlags::SharePids`].
+    ///
+    /// [`SpawnFlags::SharePids`]: ./enum.SpawnFlags.html#variant.SharePids
+    pub fn share_pids(&self) -> bool {
+        self.version >= 5 && self.expose_pids()
+    }
+
+    /// Whether the `spawn_started` signal can be requested, with [`SpawnFlags::Emit`].
+    ///
+    /// [`SpawnFlags::Emit`]: ./enum.SpawnFlags.html#variant.Emit
+    pub fn notify_start(&self) -> bool {
+        self.version >= 4
+    }
+
+    /// Whether /usr of a new sandbox can be replaced, with [`SpawnOptions::usr_fd`].
+    ///
+    /// [`SpawnOptions::usr_fd`]: ./struct.SpawnOptions.html#method.usr_fd
+    pub fn usr_overlay(&self) -> bool {
+        self.version >= 6
+    }
+
+    /// Whether /app of a new sandbox can be replaced or left empty,
+    /// with [`SpawnOptions::app_fd`] or [`SpawnFlags::EmptyApp`].
+    ///
+    /// [`SpawnOptions::app_fd`]: ./struct.SpawnOptions.html#method.app_fd
+    /// [`SpawnFlags::EmptyApp`]: ./enum.SpawnFlags.html#variant.EmptyApp
+    pub fn app_overlay(&self) -> bool {
+        self.version >= 6
+    }
+
+    /// Checks that the host can honor a spawn request.
+    ///
+    /// Returns [`Error::NotAvailable`] for a feature missing on the host and
+    /// [`Error::InvalidArgument`] for sandbox options passed without [`SpawnFlags::Sandbox`].
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - The flags of the request
+    /// * `options` - The options of the request
+    ///
+    /// [`Error::NotAvailable`]: ../enum.Error.html#variant.NotAvailable
+    /// [`Error::InvalidArgument`]: ../enum.Error.html#variant.InvalidArgument
+    /// [`SpawnFlags::Sandbox`]: ./enum.SpawnFlags.html#variant.Sandbox
+    pub fn check(&self, flags: BitFlags<SpawnFlags>, options: &SpawnOptions) -> crate::Result<()> {
+        let unavailable = |feature: &str| {
+            Err(Error::NotAvailable(format!(
+                "{} isn't supported by version {} of the Flatpak portal",
+                feature, self.version
+            )))
+        };
+        if flags.contains(SpawnFlags::Expose) && !self.expose_pids() {
+            return unavailable("exposing the sandbox pids");
+        }
+        if flags.contains(SpawnFlags::SharePids) && !self.share_pids() {
+            return unavailable("sharing the sandbox pids");
+        }
+        if flags.contains(SpawnFlags::Emit) && !self.notify_start() {
+            return unavailable("notifying the process start");
+        }
+        if options.usr_fd.is_some() && !self.usr_overlay() {
+            return unavailable("replacing /usr");
+        }
+        if (options.app_fd.is_some() || flags.contains(SpawnFlags::EmptyApp)) && !self.app_overlay()
+        {
+            return unavailable("replacing /app");
+        }
+        if options.uses_sandbox() && !flags.contains(SpawnFlags::Sandbox) {
+            return Err(Error::InvalidArgument(
+                "sandbox options require the sandbox spawn flag".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Probes the features of the Flatpak portal available on the host.
+///
+/// ```no_run
+/// use ashpd::flatpak;
+///
+/// fn main() -> ashpd::Result<()> {
+///     let capabilities = flatpak::capabilities()?;
+///     println!("Can expose pids: {}", capabilities.expose_pids());
+///     Ok(())
+/// }
+/// ```
+pub fn capabilities() -> crate::Result<Capabilities> {
+    let connection = zbus::Connection::new_session()?;
+    let proxy = FlatpakProxy::new(&connection)?;
+    capabilities_of(&proxy)
+}
+
+pub(crate) fn capabilities_of(proxy: &FlatpakProxy) -> crate::Result<Capabilities> {
+    let version = proxy.version()?;
+    // The supports property only exists since version 3.
+    let supports = if version >= 3 {
+        proxy.supports()?
+    } else {
+        BitFlags::empty()
+    };
+    Ok(Capabilities { version, supports })
+}
+
 /// Monitor if there's an update it and install it.
 pub mod update_monitor;
+
+/// Run commands on the host, for development tools.
+pub mod development;
@@ -1,5 +1,12 @@
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC};
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::ffi::OsStr;
+use std::fs::{File, OpenOptions};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::FromRawFd;
+use std::path::{Path, PathBuf};
+use std::{fmt, io};
 use zvariant_derive::Type;
 
 /// A Null terminated string.
@@ -27,6 +34,27 @@ impl From<&NString> for String {
     }
 }
 
+impl From<&OsStr> for NString {
+    fn from(t: &OsStr) -> NString {
+        let mut data = t.as_bytes().to_vec();
+        data.push(0);
+        NString(data)
+    }
+}
+
+impl From<&Path> for NString {
+    fn from(t: &Path) -> NString {
+        t.as_os_str().into()
+    }
+}
+
+impl From<&NString> for PathBuf {
+    fn from(t: &NString) -> Self {
+        let bytes = t.0.strip_suffix(&[0]).unwrap_or(&t.0);
+        PathBuf::from(OsStr::from_bytes(bytes))
+    }
+}
+
 impl fmt::Debug for NString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let t = String::from(self);
@@ -40,3 +68,46 @@ impl fmt::Display for NString {
         write!(f, "{}", t)
     }
 }
+
+/// The characters to escape when a path is embedded in a URI: everything but the
+/// unreserved characters of RFC 3986 and the path separator.
+pub(crate) const PATH_ESCAPE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'/');
+
+/// `$XDG_DATA_HOME`, defaulting to `~/.local/share`.
+///
+/// Inside a Flatpak sandbox, it points to the application's own data directory.
+pub(crate) fn data_home_dir() -> io::Result<PathBuf> {
+    match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        _ => match std::env::var_os("HOME") {
+            Some(home) => Ok(PathBuf::from(home).join(".local").join("share")),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "could not find the home directory",
+            )),
+        },
+    }
+}
+
+/// Opens `path` with `O_PATH`, which is enough for the portals to find out which file the
+/// caller is referring to, while they check by themselves what the caller is allowed to do with it.
+pub(crate) fn open_o_path(path: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_PATH)
+        .open(path)
+}
+
+/// Creates a close-on-exec pipe, returning its read and write ends.
+pub(crate) fn pipe() -> io::Result<(File, File)> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) })
+}
@@ -36,16 +36,21 @@
 //! | ---     | ----------- |
 //! | feature_gtk | Implement `Into<gdk::RGBA>` for [`Color`] |
 //! |  | Implement `From<gtk::Window>` for [`WindowIdentifier`] |
+//! | image | Decode screenshots, see [`Screenshot::decode`] |
+//! | keyring | A local keyring encrypted with the Secret portal's master secret, see [`Keyring`] |
 //!
 //!
 //! [`Color`]: ./desktop/screenshot/struct.Color.html
 //! [`WindowIdentifier`]: ./window_identifier/struct.WindowIdentifier.html
+//! [`Screenshot::decode`]: ./desktop/screenshot/struct.Screenshot.html#method.decode
+//! [`Keyring`]: ./desktop/secret/keyring/struct.Keyring.html
 //!
 // #![deny(missing_docs)] enable once
 /// Interact with the user's desktop such as taking a screenshot, setting a background or querying the user's location.
 pub mod desktop;
 /// Interact with the documents store or transfer files across apps.
 pub mod documents;
+mod error;
 /// Spawn commands outside the sandbox or monitor if the running application has received an update & install it.
 pub mod flatpak;
 mod handle_token;
@@ -53,10 +58,12 @@ mod helper;
 mod request;
 mod session;
 mod window_identifier;
+pub use self::error::{Error, Result};
 pub use self::handle_token::HandleToken;
 pub use self::helper::NString;
 pub use self::request::{BasicResponse, RequestProxy, Response, ResponseError};
 pub use self::session::SessionProxy;
 pub use self::window_identifier::WindowIdentifier;
+pub use url;
 pub use zbus;
 pub use zvariant;
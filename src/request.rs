@@ -70,11 +70,20 @@ impl<'a> RequestProxy<'a> {
     }
 
     /// A signal emitted when the portal interaction is over.
-    // FIXME: refactor once zbus supports signals
     pub fn on_response<F, T>(&self, callback: F) -> Result<()>
     where
         F: FnOnce(Response<T>),
         T: serde::de::DeserializeOwned + zvariant::Type,
+    {
+        callback(self.receive_response()?);
+        Ok(())
+    }
+
+    /// Waits for the portal interaction to be over and returns its response.
+    // FIXME: refactor once zbus supports signals
+    pub fn receive_response<T>(&self) -> Result<Response<T>>
+    where
+        T: serde::de::DeserializeOwned + zvariant::Type,
     {
         loop {
             let msg = self.connection.receive_message()?;
@@ -83,16 +92,13 @@ impl<'a> RequestProxy<'a> {
                 && msg_header.member()? == Some("Response")
             {
                 let response = msg.body::<(ResponseType, T)>()?;
-                let response = match response.0 {
+                return Ok(match response.0 {
                     ResponseType::Success => Response::Ok(response.1),
                     ResponseType::Cancelled => Response::Err(ResponseError::Cancelled),
                     ResponseType::Other => Response::Err(ResponseError::Other),
-                };
-                callback(response);
-                break;
+                });
             }
         }
-        Ok(())
     }
 
     /// Closes the portal request to which this object refers and ends all related user interaction (dialogs, etc).